@@ -6,6 +6,7 @@ use std::collections::HashMap;
 use self::MoveDirection::*;
 use cross_point::*;
 use utils::*;
+use zobrist::*;
 
 #[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
 pub enum MoveDirection {
@@ -43,6 +44,37 @@ impl Coord {
     pub fn as_i32s(&self) -> (i32, i32) {
         return (self.row as i32, self.col as i32);
     }
+
+    // columns are letters from the left starting at 'a', rows are numbers
+    // from the bottom starting at 1, the centre of 15x15 is h8, the letters
+    // end at 26 columns
+    pub fn to_notation(&self, board_size: usize) -> Result<String, Error> {
+        if self.row >= board_size || self.col >= board_size || self.col >= 26 {
+            return Err(Error::create_with_detail(ErrorKind::CoordInvalid,
+                                                 "coord has no notation on the board"));
+        }
+
+        let col = (b'a' + self.col as u8) as char;
+        return Ok(format!("{}{}", col, board_size - self.row));
+    }
+
+    pub fn from_notation(notation: &str, board_size: usize) -> Result<Coord, Error> {
+        let invalid = || Error::create_with_detail(ErrorKind::CoordInvalid,
+                                                   "invalid move notation");
+        let notation = notation.trim().to_lowercase();
+        let mut chars = notation.chars();
+        let col = match chars.next() {
+            Some(c) if c >= 'a' && c <= 'z' => c as usize - 'a' as usize,
+            _ => return Err(invalid()),
+        };
+        let row_number: usize = chars.as_str().parse().map_err(|_| invalid())?;
+
+        if col >= board_size || row_number < 1 || row_number > board_size {
+            return Err(invalid());
+        }
+
+        return Ok(Coord{row: board_size - row_number, col});
+    }
 }
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
@@ -135,6 +167,10 @@ pub struct Board {
     cross_points: Vec<Rc<CrossPoint>>,
     cross_point_linked_lists: Vec<Rc<CrossPointLinkedList>>,
     observers: RefCell<Vec<Weak<BoardObserver>>>,
+    zobrist: ZobristTable,
+    // the zobrist hash of the board in each symmetry, kept up to date
+    // on every put and remove
+    hashes: Cell<[u64; SYMMETRY_COUNT]>,
 }
 
 impl Board {
@@ -145,6 +181,8 @@ impl Board {
             cross_points: Vec::new(),
             cross_point_linked_lists: Vec::new(),
             observers: RefCell::new(Vec::new()),
+            zobrist: ZobristTable::create_with_size(15),
+            hashes: Cell::new([0; SYMMETRY_COUNT]),
         };
 
         b.initial_cross_points();
@@ -158,6 +196,8 @@ impl Board {
             cross_points: Vec::new(),
             cross_point_linked_lists: Vec::new(),
             observers: RefCell::new(Vec::new()),
+            zobrist: ZobristTable::create_with_size(size),
+            hashes: Cell::new([0; SYMMETRY_COUNT]),
         };
 
         b.initial_cross_points();
@@ -188,36 +228,41 @@ impl Board {
         return self.cross_points[self.coord_to_index(coord)].have_chess();
     }
 
-    pub fn get_chess_at(&self, coord: Coord) -> ChessType {
-        if !self.is_index_valid(coord) {
-            panic!("coord is not valid");
-        }
-
+    pub fn get_chess_at(&self, coord: Coord) -> Result<ChessType, Error> {
+        self.check_coord(coord)?;
         return self.cross_points[self.coord_to_index(coord)].get_chess();
     }
 
-    pub fn put_chess_at(&self, coord: Coord, chess: ChessType) {
-        if !self.is_index_valid(coord) {
-            panic!("coord is not valid");
-        }
-
+    pub fn put_chess_at(&self, coord: Coord, chess: ChessType) -> Result<(), Error> {
+        self.check_coord(coord)?;
         let index = self.coord_to_index(coord);
-        self.cross_points[index].put_chess(chess);
+        self.cross_points[index].put_chess(chess)?;
+        self.update_hashes(coord, chess);
 
         self.notify_observers(BoardEvent::BePutChess(CoordAndChess {coord, chess}));
+        return Ok(());
     }
 
-    pub fn remove_chess_at(&self, coord: Coord) -> ChessType {
-        if !self.is_index_valid(coord) {
-            panic!("coord is not valid");
-        }
-
+    // returns the chess removed
+    pub fn remove_chess_at(&self, coord: Coord) -> Result<ChessType, Error> {
+        self.check_coord(coord)?;
         let index = self.coord_to_index(coord);
-        let chess = self.cross_points[index].get_chess();
-        self.cross_points[index].remove_chess();
+        let chess = self.cross_points[index].remove_chess()?;
+        self.update_hashes(coord, chess);
 
         self.notify_observers(BoardEvent::BeRemoveChess(CoordAndChess {coord, chess}));
-        return chess;
+        return Ok(chess);
+    }
+
+    // the zobrist hash of the chess on the board, the side to move is
+    // not part of it
+    pub fn hash(&self) -> u64 {
+        return self.hashes.get()[0];
+    }
+
+    // the same for all the rotations and reflections of the board
+    pub fn canonical_hash(&self) -> u64 {
+        return *self.hashes.get().iter().min().unwrap();
     }
 
     pub fn get_cross_point_type_at(&self, coord: Coord) -> CrossPointType {
@@ -229,9 +274,7 @@ impl Board {
     }
 
     pub fn move_by_coord(&self, coord: Coord, md: MoveDirection) -> Result<Coord, Error> {
-        if !self.is_index_valid(coord) {
-            panic!("coord is not valid");
-        }
+        self.check_coord(coord)?;
 
         let cross_point_l = &self.cross_point_linked_lists[self.coord_to_index(coord)];
         match cross_point_l.get_next(md) {
@@ -244,9 +287,7 @@ impl Board {
 
     pub fn move_by_cross_point_l(&self, coord: Coord, md: MoveDirection)
         -> Result<Rc<CrossPointLinkedList>, Error> {
-        if !self.is_index_valid(coord) {
-            panic!("coord is not valid");
-        }
+        self.check_coord(coord)?;
 
         let cross_point_l = &self.cross_point_linked_lists[self.coord_to_index(coord)];
         match cross_point_l.get_next(md) {
@@ -296,6 +337,23 @@ impl Board {
         }
     }
 
+    // xor is its own inverse, so put and remove update the same way
+    fn update_hashes(&self, coord: Coord, chess: ChessType) {
+        let mut hashes = self.hashes.get();
+        for (hash, key) in hashes.iter_mut().zip(self.zobrist.symmetry_keys(coord, chess).iter()) {
+            *hash ^= *key;
+        }
+        self.hashes.set(hashes);
+    }
+
+    fn check_coord(&self, coord: Coord) -> Result<(), Error> {
+        match self.is_index_valid(coord) {
+            true => return Ok(()),
+            false => return Err(Error::create_with_detail(ErrorKind::CoordInvalid,
+                                                          "coord is not valid")),
+        }
+    }
+
     fn initial_cross_points(&mut self) {
         for row in 0..self.size {
             for col in 0..self.size {
@@ -1,6 +1,8 @@
 use std::rc::Rc;
 use std::cell::Cell;
 
+use utils::*;
+
 use self::ChessType::*;
 use self::CrossPointType::*;
 
@@ -45,26 +47,31 @@ impl CrossPoint {
         return self.status.get() != CptEmpty;
     }
 
-    pub fn get_chess(&self) -> ChessType {
-        return match self.status.get() {
-            CptChess(CtBlack) => CtBlack,
-            CptChess(CtWhite) => CtWhite,
-            CptEmpty => panic!("no chess in this cross point"),
+    pub fn get_chess(&self) -> Result<ChessType, Error> {
+        match self.status.get() {
+            CptChess(chess) => return Ok(chess),
+            CptEmpty => return Err(Error::create_with_detail(ErrorKind::CellEmpty,
+                                                             "no chess in this cross point")),
         }
     }
 
-    pub fn put_chess(&self, chess: ChessType) {
+    pub fn put_chess(&self, chess: ChessType) -> Result<(), Error> {
         match self.status.get() {
             CptEmpty => self.status.set(CptChess(chess)),
-            _ => panic!("there already have a chess"),
+            _ => return Err(Error::create_with_detail(ErrorKind::CellOccupied,
+                                                      "there already have a chess")),
         }
+
+        return Ok(());
     }
 
-    pub fn remove_chess(&self) {
-        match self.status.get() {
-            CptChess(_) => self.status.set(CptEmpty),
-            _ => panic!("no chess to remove here"),
-        }
+    // returns the chess removed
+    pub fn remove_chess(&self) -> Result<ChessType, Error> {
+        let chess = self.get_chess().map_err(|_| {
+            Error::create_with_detail(ErrorKind::CellEmpty, "no chess to remove here")
+        })?;
+        self.status.set(CptEmpty);
+        return Ok(chess);
     }
 
     pub fn get_cross_point_type(&self) -> CrossPointType {
@@ -1,44 +1,283 @@
+use std::collections::VecDeque;
+
 use cross_point::*;
 use board::*;
 
+use cross_point::ChessType::*;
 use cross_point::CrossPointType::*;
 use board::BoardEvent::*;
+use self::DfaInput::*;
+use self::ShapeType::*;
+
+// every pattern is at most 7 cells long, so a stone only changes the
+// patterns found within 6 cells of it on each side
+pub const EVALUATION_RADIUS: usize = 6;
+
+#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
+pub enum ShapeType {
+    StFive,
+    StOpenFour,
+    StClosedFour,
+    StOpenThree,
+    StBrokenThree,
+    StClosedThree,
+    StOpenTwo,
+    StClosedTwo,
+}
+
+// 'X' own chess, '_' empty, 'O' opponent chess or the edge of the board
+const PATTERNS: [(ShapeType, &'static str); 34] = [
+    (StFive, "XXXXX"),
+    (StOpenFour, "_XXXX_"),
+    (StClosedFour, "OXXXX_"), (StClosedFour, "_XXXXO"),
+    (StClosedFour, "X_XXX"), (StClosedFour, "XXX_X"), (StClosedFour, "XX_XX"),
+    (StOpenThree, "__XXX_"), (StOpenThree, "_XXX__"),
+    (StBrokenThree, "_XX_X_"), (StBrokenThree, "_X_XX_"),
+    (StClosedThree, "OXXX__"), (StClosedThree, "__XXXO"),
+    (StClosedThree, "OXX_X_"), (StClosedThree, "_X_XXO"),
+    (StClosedThree, "OX_XX_"), (StClosedThree, "_XX_XO"),
+    (StClosedThree, "X__XX"), (StClosedThree, "XX__X"), (StClosedThree, "X_X_X"),
+    (StClosedThree, "O_XXX_O"),
+    (StOpenTwo, "__XX__"), (StOpenTwo, "__X_X_"), (StOpenTwo, "_X_X__"),
+    (StOpenTwo, "_X__X_"),
+    (StClosedTwo, "OXX___"), (StClosedTwo, "___XXO"),
+    (StClosedTwo, "OX_X__"), (StClosedTwo, "__X_XO"),
+    (StClosedTwo, "OX__X_"), (StClosedTwo, "_X__XO"),
+    (StClosedTwo, "X___X"),
+    (StClosedTwo, "O_XX__O"), (StClosedTwo, "O__XX_O"),
+];
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
-enum DfaStatus {
+pub struct EvaluationWeights {
+    pub five: i32,
+    pub open_four: i32,
+    pub closed_four: i32,
+    pub open_three: i32,
+    pub broken_three: i32,
+    pub closed_three: i32,
+    pub open_two: i32,
+    pub closed_two: i32,
+}
+
+impl EvaluationWeights {
+    pub fn new() -> EvaluationWeights {
+        return EvaluationWeights {
+            five: 1000000,
+            open_four: 100000,
+            closed_four: 10000,
+            open_three: 4000,
+            broken_three: 6000,
+            closed_three: 500,
+            open_two: 300,
+            closed_two: 50,
+        };
+    }
 
+    pub fn get_weight(&self, shape: ShapeType) -> i32 {
+        match shape {
+            StFive => return self.five,
+            StOpenFour => return self.open_four,
+            StClosedFour => return self.closed_four,
+            StOpenThree => return self.open_three,
+            StBrokenThree => return self.broken_three,
+            StClosedThree => return self.closed_three,
+            StOpenTwo => return self.open_two,
+            StClosedTwo => return self.closed_two,
+        }
+    }
 }
 
-pub struct EvaluationDfa {
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+enum DfaInput {
+    DiChess,
+    DiEmpty,
+    DiBlocked,
+}
+
+impl DfaInput {
+    fn from_pattern_char(c: char) -> DfaInput {
+        match c {
+            'X' => return DiChess,
+            '_' => return DiEmpty,
+            'O' => return DiBlocked,
+            _ => panic!("invalid pattern char {:?}", c),
+        }
+    }
+
+    fn from_cross_point(cp: CrossPointType, chess: ChessType) -> DfaInput {
+        match cp {
+            CptEmpty => return DiEmpty,
+            CptChess(chess_cp) => match chess_cp == chess {
+                true => return DiChess,
+                false => return DiBlocked,
+            }
+        }
+    }
+
+    fn index(&self) -> usize {
+        return *self as usize;
+    }
+}
+
+// a pattern ending at the cell just read, its own chess lie from
+// first_back to last_back cells before that cell
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+struct PatternMatch {
+    shape: ShapeType,
+    first_back: usize,
+    last_back: usize,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+struct DfaStatus {
+    transitions: [usize; 3],
+    matches: Vec<PatternMatch>,
+}
+
+impl DfaStatus {
+    fn new() -> DfaStatus {
+        return DfaStatus { transitions: [0; 3], matches: Vec::new() };
+    }
+}
 
+// Aho-Corasick automaton over the patterns above, a slice is read from
+// the view of one chess type with both of its ends treated as blocked
+pub struct EvaluationDfa {
+    weights: EvaluationWeights,
+    statuses: Vec<DfaStatus>,
 }
 
 impl EvaluationDfa {
     pub fn new() -> EvaluationDfa {
-        return EvaluationDfa{};
+        return EvaluationDfa::create_with_weights(EvaluationWeights::new());
+    }
+
+    pub fn create_with_weights(weights: EvaluationWeights) -> EvaluationDfa {
+        let mut dfa = EvaluationDfa { weights, statuses: vec![DfaStatus::new()] };
+        dfa.build();
+        return dfa;
     }
 
+    pub fn weights(&self) -> EvaluationWeights {
+        return self.weights;
+    }
+
+    // score difference caused by the event, black shapes count positive
+    // and white shapes negative, cross_points holds the board after the event
     pub fn evaluate_event(&self, cross_points: &mut [CrossPointType],
                           index: usize, event: BoardEvent) -> i32 {
-        //println!("cross point type is {:?}", cross_points[index]);
-
         match event {
             BePutChess(_) => {
-                let new_score = self.evaluate_tuple(cross_points);
+                let new_score = self.evaluate_cross_points(cross_points);
                 cross_points[index] = CptEmpty;
-                let old_score = self.evaluate_tuple(cross_points);
+                let old_score = self.evaluate_cross_points(cross_points);
                 return new_score - old_score;
             },
             BeRemoveChess(coord_and_chess) => {
-                let new_score = self.evaluate_tuple(cross_points);
+                let new_score = self.evaluate_cross_points(cross_points);
                 cross_points[index] = CptChess(coord_and_chess.chess);
-                let old_score = self.evaluate_tuple(cross_points);
+                let old_score = self.evaluate_cross_points(cross_points);
                 return new_score - old_score;
             }
         }
     }
 
-    pub fn evaluate_tuple(&self, cross_points: &mut [CrossPointType]) -> i32 {
-        return 9;
+    pub fn evaluate_cross_points(&self, cross_points: &[CrossPointType]) -> i32 {
+        return self.evaluate_tuple(cross_points, CtBlack)
+            - self.evaluate_tuple(cross_points, CtWhite);
+    }
+
+    pub fn evaluate_tuple(&self, cross_points: &[CrossPointType], chess: ChessType) -> i32 {
+        let mut score = 0;
+        self.run(cross_points, chess, |shape| score += self.weights.get_weight(shape));
+        return score;
+    }
+
+    pub fn classify_tuple(&self, cross_points: &[CrossPointType], chess: ChessType)
+        -> Vec<ShapeType> {
+        let mut shapes = Vec::new();
+        self.run(cross_points, chess, |shape| shapes.push(shape));
+        return shapes;
+    }
+
+    // a shape is visited once for the same own chess, like the open three
+    // of __XXX__ which both __XXX_ and _XXX__ find
+    fn run<F>(&self, cross_points: &[CrossPointType], chess: ChessType, mut visit: F)
+        where F: FnMut(ShapeType) {
+        let mut found: Vec<(ShapeType, usize, usize)> = Vec::new();
+        let inputs = Some(DiBlocked).into_iter()
+            .chain(cross_points.iter().map(|cp| DfaInput::from_cross_point(*cp, chess)))
+            .chain(Some(DiBlocked));
+
+        let mut index = 0;
+        for (position, input) in inputs.enumerate() {
+            index = self.next(index, input);
+            for m in self.statuses[index].matches.iter() {
+                let key = (m.shape, position - m.first_back, position - m.last_back);
+                if !found.contains(&key) {
+                    found.push(key);
+                    visit(m.shape);
+                }
+            }
+        }
+    }
+
+    fn next(&self, index: usize, input: DfaInput) -> usize {
+        return self.statuses[index].transitions[input.index()];
+    }
+
+    fn build(&mut self) {
+        // trie of all patterns, 0 is both the root and the missing transition
+        for &(shape, pattern) in PATTERNS.iter() {
+            let backs: Vec<usize> = pattern.chars().rev().enumerate()
+                .filter(|&(_, c)| c == 'X').map(|(back, _)| back).collect();
+            let pattern_match = PatternMatch {
+                shape,
+                first_back: *backs.last().unwrap(),
+                last_back: backs[0],
+            };
+
+            let mut index = 0;
+            for c in pattern.chars() {
+                let input = DfaInput::from_pattern_char(c).index();
+                match self.statuses[index].transitions[input] {
+                    0 => {
+                        self.statuses.push(DfaStatus::new());
+                        let next = self.statuses.len() - 1;
+                        self.statuses[index].transitions[input] = next;
+                        index = next;
+                    },
+                    next => index = next,
+                }
+            }
+            self.statuses[index].matches.push(pattern_match);
+        }
+
+        // breadth first to fill failure links into the transitions
+        let mut fail = vec![0; self.statuses.len()];
+        let mut queue = VecDeque::new();
+        for input in 0..3 {
+            let next = self.statuses[0].transitions[input];
+            if next != 0 {
+                queue.push_back(next);
+            }
+        }
+
+        while let Some(index) = queue.pop_front() {
+            let matches_f = self.statuses[fail[index]].matches.clone();
+            self.statuses[index].matches.extend(matches_f);
+
+            for input in 0..3 {
+                let next = self.statuses[index].transitions[input];
+                let next_f = self.statuses[fail[index]].transitions[input];
+                match next {
+                    0 => self.statuses[index].transitions[input] = next_f,
+                    _ => {
+                        fail[next] = next_f;
+                        queue.push_back(next);
+                    },
+                }
+            }
+        }
     }
 }
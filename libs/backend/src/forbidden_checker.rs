@@ -0,0 +1,179 @@
+use std::cell::RefCell;
+
+use cross_point::*;
+use board::*;
+
+use cross_point::ChessType::*;
+use cross_point::CrossPointType::*;
+use self::ForbiddenType::*;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum ForbiddenType {
+    FtDoubleThree,
+    FtDoubleFour,
+    FtOverline,
+}
+
+const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+// a three is only real when its straight four point is not forbidden itself,
+// which needs recursion, give up on deeper positions and call them real
+const MAX_RECURSION_DEPTH: usize = 8;
+
+// renju restrictions for black, worked out on a copy of the board so that
+// trying stones does not notify the board observers
+pub struct ForbiddenChecker {
+    size: usize,
+    cross_points: RefCell<Vec<CrossPointType>>,
+}
+
+impl ForbiddenChecker {
+    pub fn create_with_board(board: &Board) -> ForbiddenChecker {
+        let mut cross_points = Vec::with_capacity(board.cp_count());
+        for row in 0..board.size() {
+            for col in 0..board.size() {
+                cross_points.push(board.get_cross_point_type_at(Coord{row, col}));
+            }
+        }
+
+        return ForbiddenChecker::create_with_cross_points(board.size(), cross_points);
+    }
+
+    // the cross points row by row, for boards kept apart from a Board
+    pub fn create_with_cross_points(size: usize, cross_points: Vec<CrossPointType>) -> ForbiddenChecker {
+        return ForbiddenChecker { size, cross_points: RefCell::new(cross_points) };
+    }
+
+    // whether black may not play at coord, a five always wins even if the
+    // same move also makes a forbidden shape
+    pub fn check(&self, coord: Coord) -> Option<ForbiddenType> {
+        if self.get(coord.as_i32s()) != Some(CptEmpty) {
+            return None;
+        }
+
+        return self.check_at(coord.as_i32s(), 0);
+    }
+
+    fn check_at(&self, point: (i32, i32), depth: usize) -> Option<ForbiddenType> {
+        self.set(point, CptChess(CtBlack));
+        let result = self.check_placed(point, depth);
+        self.set(point, CptEmpty);
+        return result;
+    }
+
+    fn check_placed(&self, point: (i32, i32), depth: usize) -> Option<ForbiddenType> {
+        let mut overline = false;
+        for direction in DIRECTIONS.iter() {
+            match self.run_length(point, *direction) {
+                5 => return None,
+                length if length > 5 => overline = true,
+                _ => {},
+            }
+        }
+
+        if overline {
+            return Some(FtOverline);
+        }
+
+        let mut four_count = 0;
+        let mut three_count = 0;
+        for direction in DIRECTIONS.iter() {
+            let fours = self.count_fours(point, *direction);
+            if fours > 0 {
+                four_count += fours;
+            } else if depth < MAX_RECURSION_DEPTH && self.have_three(point, *direction, depth) {
+                three_count += 1;
+            }
+        }
+
+        if four_count >= 2 {
+            return Some(FtDoubleFour);
+        } else if three_count >= 2 {
+            return Some(FtDoubleThree);
+        }
+
+        return None;
+    }
+
+    // empty points on the line which would turn the stone at point into
+    // exactly five, a straight four has two of them five apart and counts once
+    fn count_fours(&self, point: (i32, i32), direction: (i32, i32)) -> usize {
+        let offsets = self.five_points(point, direction);
+        if offsets.len() == 2 && offsets[1] - offsets[0] == 5 {
+            return 1;
+        }
+
+        return offsets.len();
+    }
+
+    fn is_straight_four(&self, point: (i32, i32), direction: (i32, i32)) -> bool {
+        let offsets = self.five_points(point, direction);
+        return offsets.len() == 2 && offsets[1] - offsets[0] == 5;
+    }
+
+    fn five_points(&self, point: (i32, i32), direction: (i32, i32)) -> Vec<i32> {
+        let mut offsets = Vec::new();
+        for offset in -4..5 {
+            let point_o = (point.0 + direction.0 * offset, point.1 + direction.1 * offset);
+            if offset == 0 || self.get(point_o) != Some(CptEmpty) {
+                continue;
+            }
+
+            self.set(point_o, CptChess(CtBlack));
+            if self.run_length(point, direction) == 5 {
+                offsets.push(offset);
+            }
+            self.set(point_o, CptEmpty);
+        }
+
+        return offsets;
+    }
+
+    // a three is a line where one more stone makes a straight four, and
+    // that stone is not forbidden for black
+    fn have_three(&self, point: (i32, i32), direction: (i32, i32), depth: usize) -> bool {
+        for offset in -4..5 {
+            let point_o = (point.0 + direction.0 * offset, point.1 + direction.1 * offset);
+            if offset == 0 || self.get(point_o) != Some(CptEmpty) {
+                continue;
+            }
+
+            self.set(point_o, CptChess(CtBlack));
+            let straight_four = self.is_straight_four(point, direction);
+            self.set(point_o, CptEmpty);
+
+            if straight_four && self.check_at(point_o, depth + 1).is_none() {
+                return true;
+            }
+        }
+
+        return false;
+    }
+
+    fn run_length(&self, point: (i32, i32), direction: (i32, i32)) -> usize {
+        let mut length = 1;
+        for sign in [-1, 1].iter() {
+            let mut point_m = (point.0 + direction.0 * sign, point.1 + direction.1 * sign);
+            while self.get(point_m) == Some(CptChess(CtBlack)) {
+                length += 1;
+                point_m = (point_m.0 + direction.0 * sign, point_m.1 + direction.1 * sign);
+            }
+        }
+
+        return length;
+    }
+
+    fn get(&self, point: (i32, i32)) -> Option<CrossPointType> {
+        let size = self.size as i32;
+        if point.0 < 0 || point.1 < 0 || point.0 >= size || point.1 >= size {
+            return None;
+        }
+
+        return Some(self.cross_points.borrow()[(point.0 * size + point.1) as usize]);
+    }
+
+    fn set(&self, point: (i32, i32), cross_point: CrossPointType) {
+        let index = (point.0 * self.size as i32 + point.1) as usize;
+        self.cross_points.borrow_mut()[index] = cross_point;
+    }
+}
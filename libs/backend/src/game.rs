@@ -0,0 +1,432 @@
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+
+use board::*;
+use cross_point::*;
+use game_tree::*;
+use position::*;
+use rule_checker::*;
+use sgf::*;
+use utils::*;
+
+use cross_point::ChessType::*;
+use rule_checker::GameStatus::*;
+use rule_checker::GameRule::*;
+use self::Lifecycle::*;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Lifecycle {
+    LcNotStarted,
+    LcSetup,
+    LcInProgress,
+    LcFinished,
+}
+
+// the lifecycle and the turn order of one game, every front end puts its
+// chess through here so that illegal moves are refused the same way,
+// taking back only moves up the game tree and the line stays for redo,
+// the chess of a set up position are on the board before the tree
+pub struct Game {
+    board: Rc<Board>,
+    rule_checker: Rc<RuleChecker>,
+    lifecycle: Cell<Lifecycle>,
+    next_chess: Cell<ChessType>,
+    first_chess: Cell<ChessType>,
+    setup: RefCell<Vec<CoordAndChess>>,
+    tree: RefCell<GameTree>,
+}
+
+impl Game {
+    pub fn new() -> Game {
+        return Game::create_with_rule(GrFreestyle);
+    }
+
+    pub fn create_with_rule(rule: GameRule) -> Game {
+        return Game::create_with_board(Board::new(), rule);
+    }
+
+    pub fn create_with_size(board_size: usize, rule: GameRule) -> Game {
+        return Game::create_with_board(Board::create_with_size(board_size), rule);
+    }
+
+    fn create_with_board(board: Rc<Board>, rule: GameRule) -> Game {
+        let rule_checker = RuleChecker::create_with_rule(board.clone(), rule);
+
+        return Game {
+            board,
+            rule_checker,
+            lifecycle: Cell::new(LcNotStarted),
+            next_chess: Cell::new(CtBlack),
+            first_chess: Cell::new(CtBlack),
+            setup: RefCell::new(Vec::new()),
+            tree: RefCell::new(GameTree::new()),
+        };
+    }
+
+    pub fn board(&self) -> Rc<Board> {
+        return self.board.clone();
+    }
+
+    pub fn rule_checker(&self) -> Rc<RuleChecker> {
+        return self.rule_checker.clone();
+    }
+
+    pub fn lifecycle(&self) -> Lifecycle {
+        return self.lifecycle.get();
+    }
+
+    pub fn game_status(&self) -> GameStatus {
+        return self.rule_checker.game_status();
+    }
+
+    pub fn next_chess(&self) -> ChessType {
+        return self.next_chess.get();
+    }
+
+    // the opening may hand the next move to either color, front ends go
+    // through the game session for it so the turns can't be skipped
+    pub(crate) fn set_next_chess(&self, chess: ChessType) {
+        self.next_chess.set(chess);
+    }
+
+    pub fn steps(&self) -> Vec<CoordAndChess> {
+        return self.tree.borrow().steps();
+    }
+
+    pub fn last_step(&self) -> Option<CoordAndChess> {
+        return self.tree.borrow().steps().last().cloned();
+    }
+
+    pub fn setup_stones(&self) -> Vec<CoordAndChess> {
+        return self.setup.borrow().clone();
+    }
+
+    pub fn current_node(&self) -> usize {
+        return self.tree.borrow().current();
+    }
+
+    pub fn node(&self, id: usize) -> Result<GameTreeNode, Error> {
+        return self.tree.borrow().node(id).map(|node| node.clone());
+    }
+
+    // the moves played from the current node before, the first one is
+    // the main line and the others are variations
+    pub fn variations(&self) -> Vec<(usize, CoordAndChess)> {
+        let tree = self.tree.borrow();
+        let node = tree.node(tree.current()).unwrap();
+        return node.children.iter()
+            .map(|id| (*id, tree.node(*id).unwrap().step.unwrap())).collect();
+    }
+
+    pub fn set_comment(&self, id: usize, comment: Option<String>) -> Result<(), Error> {
+        return self.tree.borrow_mut().set_comment(id, comment);
+    }
+
+    pub fn start(&self) -> Result<(), Error> {
+        if self.lifecycle.get() != LcNotStarted {
+            return Err(Error::create_with_detail(ErrorKind::GameAlreadyStarted,
+                                                 "the game is already started"));
+        }
+
+        self.lifecycle.set(LcInProgress);
+        self.update_lifecycle();
+        return Ok(());
+    }
+
+    // takes back every chess, the game has to be started again
+    pub fn reset(&self) -> Result<(), Error> {
+        for step in self.tree.borrow().steps().iter() {
+            self.board.remove_chess_at(step.coord)?;
+        }
+        for step in self.setup.borrow_mut().drain(..) {
+            self.board.remove_chess_at(step.coord)?;
+        }
+
+        *self.tree.borrow_mut() = GameTree::new();
+        self.next_chess.set(CtBlack);
+        self.first_chess.set(CtBlack);
+        self.lifecycle.set(LcNotStarted);
+        return Ok(());
+    }
+
+    // starts the game again and puts the coords in turn, black first
+    pub fn replay(&self, coords: &[Coord]) -> Result<(), Error> {
+        self.reset()?;
+        self.start()?;
+        for coord in coords.iter() {
+            self.put_chess(*coord, self.next_chess.get())?;
+        }
+
+        return Ok(());
+    }
+
+    // the chess on the board become the set up position and can be put
+    // or removed freely until finish_setup, the moves played are dropped
+    pub fn begin_setup(&self) -> Result<(), Error> {
+        let steps = self.tree.borrow().steps();
+        self.setup.borrow_mut().extend(steps);
+        *self.tree.borrow_mut() = GameTree::new();
+        self.lifecycle.set(LcSetup);
+        return Ok(());
+    }
+
+    pub fn setup_put(&self, coord: Coord, chess: ChessType) -> Result<(), Error> {
+        self.check_setup()?;
+        self.board.put_chess_at(coord, chess)?;
+        self.setup.borrow_mut().push(CoordAndChess{coord, chess});
+        return Ok(());
+    }
+
+    pub fn setup_remove(&self, coord: Coord) -> Result<ChessType, Error> {
+        self.check_setup()?;
+        let chess = self.board.remove_chess_at(coord)?;
+        self.setup.borrow_mut().retain(|step| step.coord != coord);
+        return Ok(chess);
+    }
+
+    // the game goes on from the set up position with next_chess to move,
+    // a position which is already over or which could not come from
+    // moves in turn is refused and the setup goes on
+    pub fn finish_setup(&self, next_chess: ChessType) -> Result<(), Error> {
+        self.check_setup()?;
+        if self.rule_checker.game_status() != GsGameContinue {
+            return Err(Error::create_with_detail(ErrorKind::PositionInvalid,
+                                                 "the position is already over"));
+        }
+
+        let black_count = self.setup.borrow().iter().filter(|step| step.chess == CtBlack).count();
+        let white_count = self.setup.borrow().len() - black_count;
+        if black_count < white_count || black_count > white_count + 1 {
+            return Err(Error::create_with_detail(ErrorKind::PositionInvalid,
+                                                 "black needs as many chess as white or one more"));
+        }
+
+        // black is to move with as many chess as white, white with one less
+        let expected_chess = match black_count == white_count {
+            true => CtBlack,
+            false => CtWhite,
+        };
+        if next_chess != expected_chess {
+            return Err(Error::create_with_detail(ErrorKind::PositionInvalid,
+                                                 "the side to move does not fit the chess on the board"));
+        }
+
+        self.next_chess.set(next_chess);
+        self.first_chess.set(next_chess);
+        self.lifecycle.set(LcInProgress);
+        return Ok(());
+    }
+
+    pub fn position(&self) -> Position {
+        return Position::create_with_board(&self.board, self.next_chess.get());
+    }
+
+    // starts the game again from the position as if it was set up
+    pub fn load_position(&self, position: &Position) -> Result<(), Error> {
+        if position.board_size() != self.board.size() {
+            return Err(Error::create_with_detail(ErrorKind::PositionInvalid,
+                                                 "board size of the position is not supported"));
+        }
+
+        self.reset()?;
+        self.begin_setup()?;
+        for stone in position.stones().iter() {
+            self.setup_put(stone.coord, stone.chess)?;
+        }
+        return self.finish_setup(position.next_chess());
+    }
+
+    // like replay but every step brings its own chess, which still has
+    // to be in turn
+    pub fn replay_steps(&self, steps: &[CoordAndChess]) -> Result<(), Error> {
+        self.reset()?;
+        self.start()?;
+        for step in steps.iter() {
+            self.put_chess(step.coord, step.chess)?;
+        }
+
+        return Ok(());
+    }
+
+    pub fn put_chess(&self, coord: Coord, chess: ChessType) -> Result<(), Error> {
+        self.check_in_progress()?;
+        if chess != self.next_chess.get() {
+            return Err(Error::create_with_detail(ErrorKind::WrongTurn,
+                                                 "it is the other side's turn"));
+        }
+
+        self.rule_checker.put_chess_at(coord, chess)?;
+        let mut tree = self.tree.borrow_mut();
+        let current = tree.current();
+        let child = tree.add_child(current, CoordAndChess{coord, chess})?;
+        tree.set_current(child)?;
+        self.next_chess.set(chess.get_different_chess());
+        self.update_lifecycle();
+        return Ok(());
+    }
+
+    // takes back the last chess, which is the one to move again,
+    // None when there is nothing to take back
+    pub fn take_back(&self) -> Result<Option<CoordAndChess>, Error> {
+        self.check_started()?;
+
+        let (step, parent) = {
+            let tree = self.tree.borrow();
+            let node = tree.node(tree.current())?;
+            match (node.step, node.parent) {
+                (Some(step), Some(parent)) => (step, parent),
+                _ => return Ok(None),
+            }
+        };
+
+        self.board.remove_chess_at(step.coord)?;
+        self.tree.borrow_mut().set_current(parent)?;
+        self.next_chess.set(step.chess);
+        self.lifecycle.set(LcInProgress);
+        self.update_lifecycle();
+        return Ok(Some(step));
+    }
+
+    // puts the chess taken back last again, or the main line when the
+    // current node was never left, None at the end of the line
+    pub fn redo(&self) -> Result<Option<CoordAndChess>, Error> {
+        let tree_current = self.tree.borrow().current();
+        let child = match self.tree.borrow().next_child(tree_current)? {
+            Some(child) => child,
+            None => return Ok(None),
+        };
+
+        let step = self.tree.borrow().node(child)?.step.unwrap();
+        self.put_chess(step.coord, step.chess)?;
+        return Ok(Some(step));
+    }
+
+    // takes back and puts chess until the board is at the node
+    pub fn go_to(&self, id: usize) -> Result<(), Error> {
+        self.check_started()?;
+        let target = self.tree.borrow().path_to(id)?;
+        let current = self.tree.borrow().path_to(self.current_node())?;
+        let common = target.iter().zip(current.iter()).take_while(|&(a, b)| a == b).count();
+
+        for _ in common..current.len() {
+            self.take_back()?;
+        }
+        for node_id in target[common..].iter() {
+            let step = self.tree.borrow().node(*node_id)?.step.unwrap();
+            self.put_chess(step.coord, step.chess)?;
+        }
+
+        return Ok(());
+    }
+
+    // the board after the first number steps of the line, further steps
+    // follow the line which redo would go down
+    pub fn jump_to(&self, number: usize) -> Result<(), Error> {
+        let mut line = self.tree.borrow().path_to(self.current_node())?;
+        while line.len() < number {
+            let last = line.last().cloned().unwrap_or(GAME_TREE_ROOT);
+            match self.tree.borrow().next_child(last)? {
+                Some(child) => line.push(child),
+                None => return Err(Error::create_with_detail(ErrorKind::NodeNotFound,
+                                                             "the line is not that long")),
+            }
+        }
+
+        match number {
+            0 => return self.go_to(GAME_TREE_ROOT),
+            _ => return self.go_to(line[number - 1]),
+        }
+    }
+
+    // goes over to the next variation of the last move, back to the
+    // first after the last one, false when there is no other variation
+    pub fn next_variation(&self) -> Result<bool, Error> {
+        let sibling = {
+            let tree = self.tree.borrow();
+            let current = tree.current();
+            let parent = match tree.node(current)?.parent {
+                Some(parent) => parent,
+                None => return Ok(false),
+            };
+
+            let siblings = &tree.node(parent)?.children;
+            let index = siblings.iter().position(|id| *id == current).unwrap();
+            siblings[(index + 1) % siblings.len()]
+        };
+
+        if sibling == self.current_node() {
+            return Ok(false);
+        }
+
+        self.go_to(sibling)?;
+        return Ok(true);
+    }
+
+    pub fn to_sgf(&self) -> SgfRecord {
+        return SgfRecord {
+            board_size: self.board.size(),
+            rule: self.rule_checker.rule(),
+            setup: self.setup.borrow().clone(),
+            first_chess: self.first_chess.get(),
+            root: self.tree.borrow().to_sgf_node(GAME_TREE_ROOT).unwrap(),
+        };
+    }
+
+    // starts the game again with the tree of the record and plays
+    // through its main line
+    pub fn load_sgf(&self, record: &SgfRecord) -> Result<(), Error> {
+        self.reset()?;
+        match record.setup.is_empty() && record.first_chess == CtBlack {
+            true => self.start()?,
+            false => {
+                self.begin_setup()?;
+                for step in record.setup.iter() {
+                    self.setup_put(step.coord, step.chess)?;
+                }
+                self.finish_setup(record.first_chess)?;
+            },
+        }
+        self.tree.borrow_mut().add_sgf_node(GAME_TREE_ROOT, &record.root)?;
+
+        while self.lifecycle.get() == LcInProgress && self.redo()?.is_some() {}
+        return Ok(());
+    }
+
+    fn check_started(&self) -> Result<(), Error> {
+        match self.lifecycle.get() {
+            LcNotStarted | LcSetup => return Err(Error::create_with_detail(
+                ErrorKind::GameNotStarted, "the game is not started")),
+            LcInProgress | LcFinished => return Ok(()),
+        }
+    }
+
+    fn check_setup(&self) -> Result<(), Error> {
+        if self.lifecycle.get() != LcSetup {
+            return Err(Error::create_with_detail(ErrorKind::GameAlreadyStarted,
+                                                 "the position is not being set up"));
+        }
+
+        return Ok(());
+    }
+
+    fn check_in_progress(&self) -> Result<(), Error> {
+        match self.lifecycle.get() {
+            LcNotStarted | LcSetup => return Err(Error::create_with_detail(
+                ErrorKind::GameNotStarted, "the game is not started")),
+            LcFinished => return Err(Error::create_with_detail(ErrorKind::GameAlreadyOver,
+                                                               "the game is already over")),
+            LcInProgress => return Ok(()),
+        }
+    }
+
+    fn update_lifecycle(&self) {
+        if self.lifecycle.get() == LcNotStarted || self.lifecycle.get() == LcSetup {
+            return;
+        }
+
+        match self.rule_checker.game_status() {
+            GsGameContinue => self.lifecycle.set(LcInProgress),
+            GsGameOver(_) | GsDraw => self.lifecycle.set(LcFinished),
+        }
+    }
+}
@@ -0,0 +1,466 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::fs;
+
+use board::*;
+use cross_point::*;
+use game::*;
+use notation::*;
+use opening::*;
+use psq::*;
+use rif::*;
+use rule_checker::*;
+use sgf::*;
+use utils::*;
+
+use cross_point::ChessType::*;
+use game::Lifecycle::*;
+use opening::OpeningPhase::*;
+use opening::OpeningRule::*;
+use rule_checker::GameRule::*;
+use rule_checker::GameStatus::*;
+use self::RecordFormat::*;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum RecordFormat {
+    RfSgf,
+    RfPsq,
+    RfRif,
+    // moves in algebraic notation like "h8 i9 j10"
+    RfNotation,
+}
+
+impl RecordFormat {
+    // the format follows the file extension, anything unknown is notation
+    pub fn from_path(path: &str) -> RecordFormat {
+        let lower = path.to_lowercase();
+        if lower.ends_with(".sgf") {
+            return RfSgf;
+        } else if lower.ends_with(".psq") {
+            return RfPsq;
+        } else if lower.ends_with(".rif") || lower.ends_with(".xml") {
+            return RfRif;
+        } else {
+            return RfNotation;
+        }
+    }
+}
+
+// a cross point which differs from before, chess is None once emptied
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct CellChange {
+    pub coord: Coord,
+    pub chess: Option<ChessType>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct OpeningStatus {
+    pub phase: OpeningPhase,
+    // the player to act, or the one to move once the opening is over
+    pub player: Player,
+    pub actions: Vec<OpeningAction>,
+    // the offered fifth moves waiting for one to be selected
+    pub alternatives: Vec<Coord>,
+}
+
+// a game together with its opening, the way a front end plays it: moves
+// for the side to move, taking back and going through the game tree,
+// setting up positions and saving and loading records
+pub struct GameSession {
+    game: Game,
+    opening: RefCell<Opening>,
+}
+
+impl GameSession {
+    pub fn new() -> GameSession {
+        return GameSession::create_with_rule(GrFreestyle);
+    }
+
+    pub fn create_with_rule(rule: GameRule) -> GameSession {
+        return GameSession::create_with_game(Game::create_with_rule(rule));
+    }
+
+    pub fn create_with_size(board_size: usize, rule: GameRule) -> GameSession {
+        return GameSession::create_with_game(Game::create_with_size(board_size, rule));
+    }
+
+    fn create_with_game(game: Game) -> GameSession {
+        let opening = RefCell::new(Opening::create_with_rule(OrFree, game.board().size()));
+        game.start().unwrap();
+
+        return GameSession {
+            game,
+            opening,
+        };
+    }
+
+    pub fn game(&self) -> &Game {
+        return &self.game;
+    }
+
+    pub fn board(&self) -> Rc<Board> {
+        return self.game.board();
+    }
+
+    pub fn rule(&self) -> GameRule {
+        return self.game.rule_checker().rule();
+    }
+
+    pub fn board_size(&self) -> usize {
+        return self.game.board().size();
+    }
+
+    pub fn next_chess(&self) -> ChessType {
+        return self.game.next_chess();
+    }
+
+    // the side to move puts its chess, the opening goes through put_opening_chess
+    pub fn put_chess(&self, coord: Coord) -> Result<(), Error> {
+        if !self.is_opening_finished() {
+            return Err(Error::create_with_detail(ErrorKind::OpeningActionInvalid,
+                                                 "the opening is not finished"));
+        }
+
+        return self.game.put_chess(coord, self.game.next_chess());
+    }
+
+    // the chess of the opening can not be taken back one by one,
+    // None when there is nothing to take back
+    pub fn take_back(&self) -> Result<Option<CoordAndChess>, Error> {
+        if !self.can_take_back() {
+            return Ok(None);
+        }
+
+        return self.game.take_back();
+    }
+
+    // puts the chess taken back last again, None at the end of the line
+    pub fn forward_move(&self) -> Result<Option<CoordAndChess>, Error> {
+        if !self.is_opening_finished() {
+            return Ok(None);
+        }
+
+        return self.game.redo();
+    }
+
+    // switches to the next variation of the last move, the chess of the
+    // opening have none
+    pub fn next_variation(&self) -> Result<bool, Error> {
+        if !self.can_take_back() {
+            return Ok(false);
+        }
+
+        return self.game.next_variation();
+    }
+
+    // takes back until a chess of the color is taken back, which is the
+    // bot reply and the move before it when playing against the bot
+    pub fn undo_to_my_last_move(&self, chess: ChessType) -> Result<(), Error> {
+        while self.can_take_back() {
+            match self.game.take_back()? {
+                Some(step) if step.chess == chess => break,
+                Some(_) => {},
+                None => break,
+            }
+        }
+
+        return Ok(());
+    }
+
+    // the board after the first number moves of the line, the chess of
+    // the opening can not be jumped over
+    pub fn jump_to_move(&self, number: usize) -> Result<(), Error> {
+        if !self.is_opening_finished() || number < self.opening.borrow().chess_count() {
+            return Err(Error::create_with_detail(ErrorKind::NodeNotFound,
+                                                 "can not jump into the opening"));
+        }
+
+        return self.game.jump_to(number);
+    }
+
+    // the cells which differ from the steps before
+    pub fn changed_cells(&self, steps_before: &[CoordAndChess]) -> Vec<CellChange> {
+        let steps = self.game.steps();
+        let mut changed: Vec<CellChange> = steps_before.iter()
+            .filter(|step| !steps.iter().any(|other| other.coord == step.coord))
+            .map(|step| CellChange{coord: step.coord, chess: None}).collect();
+        changed.extend(steps.iter().filter(|step| !steps_before.contains(step))
+            .map(|step| CellChange{coord: step.coord, chess: Some(step.chess)}));
+
+        return changed;
+    }
+
+    // the chess on the board can be changed freely until finish_setup, the
+    // moves played so far stay as the set up position and the opening is
+    // given up for it
+    pub fn begin_setup(&self) -> Result<(), Error> {
+        self.game.begin_setup()?;
+        *self.opening.borrow_mut() = Opening::create_with_rule(OrFree, self.game.board().size());
+        return Ok(());
+    }
+
+    pub fn is_in_setup(&self) -> bool {
+        return self.game.lifecycle() == LcSetup;
+    }
+
+    // None empties the cross point, a chess of the other color is replaced
+    pub fn setup_chess(&self, coord: Coord, chess: Option<ChessType>) -> Result<(), Error> {
+        let current = match self.game.board().get_chess_at(coord) {
+            Ok(chess) => Some(chess),
+            Err(ref error) if error.kind == ErrorKind::CellEmpty => None,
+            Err(error) => return Err(error),
+        };
+        if current == chess {
+            return Ok(());
+        }
+
+        if current.is_some() {
+            self.game.setup_remove(coord)?;
+        }
+        if let Some(chess) = chess {
+            self.game.setup_put(coord, chess)?;
+        }
+        return Ok(());
+    }
+
+    // chess is the side to move in the set up position
+    pub fn finish_setup(&self, chess: ChessType) -> Result<(), Error> {
+        return self.game.finish_setup(chess);
+    }
+
+    pub fn setup_stones(&self) -> Vec<CoordAndChess> {
+        return self.game.setup_stones();
+    }
+
+    fn can_take_back(&self) -> bool {
+        let opening_chess_count = self.opening.borrow().chess_count();
+        return self.is_opening_finished() && self.game.steps().len() > opening_chess_count;
+    }
+
+    // how many moves have been tried from the current position
+    pub fn variation_count(&self) -> usize {
+        return self.game.variations().len();
+    }
+
+    pub fn last_step(&self) -> Option<CoordAndChess> {
+        return self.game.last_step();
+    }
+
+    pub fn steps(&self) -> Vec<CoordAndChess> {
+        return self.game.steps();
+    }
+
+    pub fn is_over(&self) -> bool {
+        return self.game.lifecycle() == LcFinished;
+    }
+
+    pub fn is_draw(&self) -> bool {
+        return self.game.game_status() == GsDraw;
+    }
+
+    pub fn winner(&self) -> Option<ChessType> {
+        match self.game.game_status() {
+            GsGameOver(winner) => return Some(winner),
+            GsDraw | GsGameContinue => return None,
+        }
+    }
+
+    // the chess making five, empty until the game is over
+    pub fn winning_line(&self) -> Vec<Coord> {
+        return self.game.rule_checker().winning_line().unwrap_or_default();
+    }
+
+    pub fn restart(&self) -> Result<(), Error> {
+        self.game.reset()?;
+        self.game.start()?;
+
+        let rule = self.opening.borrow().rule();
+        *self.opening.borrow_mut() = Opening::create_with_rule(rule, self.board_size());
+        return Ok(());
+    }
+
+    pub fn save_game(&self, path: &str) -> Result<(), Error> {
+        let steps = self.game.steps();
+        let coords: Vec<Coord> = steps.iter().map(|step| step.coord).collect();
+        let board_size = self.board_size();
+        let format = RecordFormat::from_path(path);
+        if format != RfSgf && !self.game.setup_stones().is_empty() {
+            return Err(Error::create_with_detail(ErrorKind::RecordInvalid,
+                                                 "only sgf keeps a set up position"));
+        }
+
+        let text = match format {
+            RfSgf => self.game.to_sgf().write(),
+            RfPsq => PsqRecord::create_with_coords(board_size, &coords).write(),
+            RfRif => {
+                let mut database = RifDatabase::new();
+                database.records.push(RifRecord::create_with_moves(RifRecordType::RtGame, coords));
+                database.write()?
+            },
+            RfNotation => write_moves(&coords, board_size)? + "\n",
+        };
+
+        return fs::write(path, text).map_err(|error| {
+            Error::create_with_detail(ErrorKind::IoFailed, &error.to_string())
+        });
+    }
+
+    // replaces the game with the record, the board is left empty when
+    // the record can not be played through
+    pub fn load_game(&self, path: &str) -> Result<(), Error> {
+        let text = fs::read_to_string(path).map_err(|error| {
+            Error::create_with_detail(ErrorKind::IoFailed, &error.to_string())
+        })?;
+        let board_size = self.board_size();
+        let rule = self.game.rule_checker().rule();
+        let record = match RecordFormat::from_path(path) {
+            RfSgf => SgfRecord::parse(&text)?,
+            RfPsq => {
+                let record = PsqRecord::parse(&text)?;
+                SgfRecord::create_with_steps(record.board_size, rule,
+                                             &steps_in_turn(&record.coords()))
+            },
+            RfRif => {
+                let database = RifDatabase::parse(&text)?;
+                match database.records_of_type(RifRecordType::RtGame).first() {
+                    Some(record) => SgfRecord::create_with_steps(RIF_BOARD_SIZE, rule,
+                                                                 &steps_in_turn(&record.moves)),
+                    None => return Err(Error::create_with_detail(ErrorKind::RecordInvalid,
+                                                                 "no game in the database")),
+                }
+            },
+            RfNotation => SgfRecord::create_with_steps(board_size, rule,
+                                                       &steps_in_turn(&parse_moves(&text, board_size)?)),
+        };
+        if record.board_size != board_size {
+            return Err(Error::create_with_detail(ErrorKind::RecordInvalid,
+                                                 "board size of the record is not supported"));
+        }
+
+        self.set_opening_rule(OrFree)?;
+        if let Err(error) = self.game.load_sgf(&record) {
+            self.restart()?;
+            return Err(error);
+        }
+
+        return Ok(());
+    }
+
+    // the game starts again under the new opening
+    pub fn set_opening_rule(&self, rule: OpeningRule) -> Result<(), Error> {
+        *self.opening.borrow_mut() = Opening::create_with_rule(rule, self.board_size());
+        return self.restart();
+    }
+
+    pub fn is_opening_finished(&self) -> bool {
+        return self.opening.borrow().is_finished();
+    }
+
+    // returns the chess put, its color is decided by the opening
+    pub fn put_opening_chess(&self, coord: Coord) -> Result<ChessType, Error> {
+        let board = self.game.board();
+        if !board.is_index_valid(coord) {
+            return Err(Error::create_with_detail(ErrorKind::CoordInvalid, "coord is not valid"));
+        } else if board.have_chess_at(coord) {
+            return Err(Error::create_with_detail(ErrorKind::CellOccupied,
+                                                 "there already have a chess"));
+        }
+
+        // an offered fifth move is only a mark until it is selected
+        if self.opening.borrow().phase() == OpPlaceAlternative {
+            let chess = self.opening.borrow().place(coord)?;
+            self.update_chess_by_opening();
+            return Ok(chess);
+        }
+
+        // the opening only moves on once the chess is on the board
+        let saved = self.opening.borrow().clone();
+        let next_chess = self.game.next_chess();
+        let chess = self.opening.borrow().place(coord)?;
+        self.game.set_next_chess(chess);
+        if let Err(error) = self.game.put_chess(coord, chess) {
+            *self.opening.borrow_mut() = saved;
+            self.game.set_next_chess(next_chess);
+            return Err(error);
+        }
+
+        self.update_chess_by_opening();
+        return Ok(chess);
+    }
+
+    pub fn choose_color(&self, chess: ChessType) -> Result<(), Error> {
+        self.opening.borrow().choose_color(chess)?;
+        self.update_chess_by_opening();
+        return Ok(());
+    }
+
+    pub fn place_two(&self) -> Result<(), Error> {
+        self.opening.borrow().place_two()?;
+        self.update_chess_by_opening();
+        return Ok(());
+    }
+
+    pub fn declare_alternatives(&self, count: usize) -> Result<(), Error> {
+        self.opening.borrow().declare_alternatives(count)?;
+        self.update_chess_by_opening();
+        return Ok(());
+    }
+
+    pub fn offer_alternatives(&self) -> Result<(), Error> {
+        self.opening.borrow().offer_alternatives()?;
+        self.update_chess_by_opening();
+        return Ok(());
+    }
+
+    // puts the fifth move at coord on the board, the other offered ones
+    // are dropped and returned
+    pub fn select_alternative(&self, coord: Coord) -> Result<Vec<Coord>, Error> {
+        let saved = self.opening.borrow().clone();
+        let next_chess = self.game.next_chess();
+        let others = self.opening.borrow().select_alternative(coord)?;
+        self.game.set_next_chess(CtBlack);
+        if let Err(error) = self.game.put_chess(coord, CtBlack) {
+            *self.opening.borrow_mut() = saved;
+            self.game.set_next_chess(next_chess);
+            return Err(error);
+        }
+
+        self.update_chess_by_opening();
+        return Ok(others);
+    }
+
+    pub fn opening_status(&self) -> OpeningStatus {
+        let opening = self.opening.borrow();
+        let player = match opening.is_finished() {
+            true => opening.get_player(self.game.next_chess()),
+            false => opening.current_player(),
+        };
+
+        return OpeningStatus {
+            phase: opening.phase(),
+            player,
+            actions: opening.legal_actions(),
+            alternatives: opening.alternatives(),
+        };
+    }
+
+    fn update_chess_by_opening(&self) {
+        match self.opening.borrow().phase() {
+            OpPlaceChess(chess) => self.game.set_next_chess(chess),
+            OpPlaceAlternative | OpTaraguchiFifth => self.game.set_next_chess(CtBlack),
+            OpFinished => self.game.set_next_chess(CtWhite),
+            _ => {},
+        }
+    }
+}
+
+// the coords of records without colors are put in turn, black first
+fn steps_in_turn(coords: &[Coord]) -> Vec<CoordAndChess> {
+    return coords.iter().enumerate().map(|(index, coord)| {
+        let chess = match index % 2 == 0 {
+            true => CtBlack,
+            false => CtWhite,
+        };
+        CoordAndChess{coord: *coord, chess}
+    }).collect();
+}
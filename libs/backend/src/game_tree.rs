@@ -0,0 +1,141 @@
+use board::*;
+use sgf::*;
+use utils::*;
+
+// the root stands for the empty board and never has a step
+pub const GAME_TREE_ROOT: usize = 0;
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct GameTreeNode {
+    pub step: Option<CoordAndChess>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    pub comment: Option<String>,
+    // the child the line went on with last time, redo follows it
+    pub selected: Option<usize>,
+}
+
+impl GameTreeNode {
+    fn create_with_step(step: Option<CoordAndChess>, parent: Option<usize>) -> GameTreeNode {
+        return GameTreeNode { step, parent, children: Vec::new(), comment: None, selected: None };
+    }
+}
+
+// every line played from the empty board, the nodes are kept in one vec
+// and refer to each other by index, so the ids stay valid while the
+// tree only grows
+pub struct GameTree {
+    nodes: Vec<GameTreeNode>,
+    current: usize,
+}
+
+impl GameTree {
+    pub fn new() -> GameTree {
+        return GameTree {
+            nodes: vec![GameTreeNode::create_with_step(None, None)],
+            current: GAME_TREE_ROOT,
+        };
+    }
+
+    pub fn current(&self) -> usize {
+        return self.current;
+    }
+
+    pub fn node(&self, id: usize) -> Result<&GameTreeNode, Error> {
+        return self.nodes.get(id).ok_or_else(|| {
+            Error::create_with_detail(ErrorKind::NodeNotFound, "no such node in the game tree")
+        });
+    }
+
+    // the node ids from the first step down to id, the root left out
+    pub fn path_to(&self, id: usize) -> Result<Vec<usize>, Error> {
+        let mut path = Vec::new();
+        let mut node = self.node(id)?;
+        let mut node_id = id;
+        while let Some(parent) = node.parent {
+            path.push(node_id);
+            node_id = parent;
+            node = &self.nodes[parent];
+        }
+
+        path.reverse();
+        return Ok(path);
+    }
+
+    // the steps played to reach the current node
+    pub fn steps(&self) -> Vec<CoordAndChess> {
+        return self.path_to(self.current).unwrap().iter()
+            .filter_map(|id| self.nodes[*id].step).collect();
+    }
+
+    // an existing child with the same step is reused, so playing a move
+    // again does not make a variation of itself
+    pub fn add_child(&mut self, parent: usize, step: CoordAndChess) -> Result<usize, Error> {
+        if let Some(child) = self.child_with_step(parent, step)? {
+            return Ok(child);
+        }
+
+        let child = self.nodes.len();
+        self.nodes.push(GameTreeNode::create_with_step(Some(step), Some(parent)));
+        self.nodes[parent].children.push(child);
+        return Ok(child);
+    }
+
+    pub fn child_with_step(&self, parent: usize,
+                           step: CoordAndChess) -> Result<Option<usize>, Error> {
+        return Ok(self.node(parent)?.children.iter().cloned()
+            .find(|child| self.nodes[*child].step == Some(step)));
+    }
+
+    // the child redo goes to, the selected one or else the main line
+    pub fn next_child(&self, id: usize) -> Result<Option<usize>, Error> {
+        let node = self.node(id)?;
+        return Ok(node.selected.or_else(|| node.children.first().cloned()));
+    }
+
+    // moves the current node and remembers the way down to it
+    pub fn set_current(&mut self, id: usize) -> Result<(), Error> {
+        for node_id in self.path_to(id)? {
+            let parent = self.nodes[node_id].parent.unwrap();
+            self.nodes[parent].selected = Some(node_id);
+        }
+
+        self.current = id;
+        return Ok(());
+    }
+
+    pub fn set_comment(&mut self, id: usize, comment: Option<String>) -> Result<(), Error> {
+        self.node(id)?;
+        self.nodes[id].comment = comment;
+        return Ok(());
+    }
+
+    pub fn to_sgf_node(&self, id: usize) -> Result<SgfNode, Error> {
+        let node = self.node(id)?;
+        let mut sgf_node = SgfNode { step: node.step, comment: node.comment.clone(),
+                                     children: Vec::new() };
+        for child in node.children.iter() {
+            sgf_node.children.push(self.to_sgf_node(*child)?);
+        }
+
+        return Ok(sgf_node);
+    }
+
+    // adds the steps of the sgf node and the ones under it below parent,
+    // the sgf root may carry a step of its own
+    pub fn add_sgf_node(&mut self, parent: usize, sgf_node: &SgfNode) -> Result<(), Error> {
+        let id = match sgf_node.step {
+            Some(step) => self.add_child(parent, step)?,
+            None => parent,
+        };
+        if sgf_node.comment.is_some() {
+            self.nodes[id].comment = sgf_node.comment.clone();
+        }
+
+        for child in sgf_node.children.iter() {
+            self.add_sgf_node(id, child)?;
+        }
+
+        return Ok(());
+    }
+}
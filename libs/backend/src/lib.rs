@@ -1,6 +1,15 @@
-extern crate slice_deque;
-
+pub mod game;
+pub mod game_session;
+pub mod game_tree;
 pub mod rule_checker;
+pub mod forbidden_checker;
+pub mod opening;
+pub mod psq;
+pub mod notation;
+pub mod rif;
+pub mod sgf;
+pub mod position;
+pub mod zobrist;
 pub mod evaluation_dfa;
 pub mod tuple;
 pub mod board;
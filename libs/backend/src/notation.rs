@@ -0,0 +1,35 @@
+use board::*;
+use utils::*;
+
+// moves in algebraic notation one after another, like "h8 i9 j10",
+// published records often leave out the spaces which is read as well
+pub fn write_moves(coords: &[Coord], board_size: usize) -> Result<String, Error> {
+    let notations = coords.iter()
+        .map(|coord| coord.to_notation(board_size)).collect::<Result<Vec<String>, Error>>()?;
+    return Ok(notations.join(" "));
+}
+
+pub fn parse_moves(text: &str, board_size: usize) -> Result<Vec<Coord>, Error> {
+    let mut coords = Vec::new();
+    let mut notation = String::new();
+
+    for c in text.chars() {
+        if c.is_ascii_alphabetic() && !notation.is_empty() {
+            coords.push(Coord::from_notation(&notation, board_size)?);
+            notation.clear();
+        }
+
+        if c.is_ascii_alphanumeric() {
+            notation.push(c);
+        } else if !c.is_whitespace() && c != ',' && c != ';' {
+            return Err(Error::create_with_detail(ErrorKind::RecordInvalid,
+                                                 "unexpected char in moves"));
+        }
+    }
+
+    if !notation.is_empty() {
+        coords.push(Coord::from_notation(&notation, board_size)?);
+    }
+
+    return Ok(coords);
+}
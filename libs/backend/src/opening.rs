@@ -0,0 +1,322 @@
+use std::cell::{Cell, RefCell};
+
+use cross_point::*;
+use board::*;
+use utils::*;
+
+use cross_point::ChessType::*;
+use self::OpeningRule::*;
+use self::OpeningPhase::*;
+use self::OpeningAction::*;
+use self::Player::*;
+
+pub const SOOSORV_MAX_ALTERNATIVES: usize = 8;
+pub const TARAGUCHI_ALTERNATIVES: usize = 10;
+
+// OrFree: black just starts
+// OrSwap: first player puts black, white, black, second player picks a colour
+// OrSwap2: like swap, but the second player may also put two more stones
+//          and leave the choice of colour to the first player
+// OrSoosorv: swap, white puts the 4th move and declares up to 8 fifth moves,
+//            swap again, black offers the fifth moves and white keeps one
+// OrTaraguchi: the first four moves go inside growing squares around the
+//              centre with a swap after each, black then puts the 5th move
+//              inside 9x9 followed by a swap, or offers 10 fifth moves
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum OpeningRule {
+    OrFree,
+    OrSwap,
+    OrSwap2,
+    OrSoosorv,
+    OrTaraguchi,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Player {
+    PlFirst,
+    PlSecond,
+}
+
+impl Player {
+    pub fn get_other_player(&self) -> Player {
+        match *self {
+            PlFirst => return PlSecond,
+            PlSecond => return PlFirst,
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum OpeningPhase {
+    OpPlaceChess(ChessType),
+    OpChooseColor,
+    OpSwap2Choice,
+    OpDeclareAlternatives,
+    OpPlaceAlternative,
+    OpSelectAlternative,
+    OpTaraguchiFifth,
+    OpFinished,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum OpeningAction {
+    OaPlace,
+    OaChooseColor,
+    OaPlaceTwo,
+    OaDeclareAlternatives,
+    OaOfferAlternatives,
+    OaSelectAlternative,
+}
+
+#[derive(Clone)]
+pub struct Opening {
+    rule: OpeningRule,
+    board_size: usize,
+    phase: Cell<OpeningPhase>,
+    actor: Cell<Player>,
+    black_player: Cell<Player>,
+    chess_count: Cell<usize>,
+    alternative_count: Cell<usize>,
+    alternatives: RefCell<Vec<Coord>>,
+}
+
+impl Opening {
+    pub fn create_with_rule(rule: OpeningRule, board_size: usize) -> Opening {
+        let phase = match rule {
+            OrFree => OpFinished,
+            _ => OpPlaceChess(CtBlack),
+        };
+
+        return Opening {
+            rule,
+            board_size,
+            phase: Cell::new(phase),
+            actor: Cell::new(PlFirst),
+            black_player: Cell::new(PlFirst),
+            chess_count: Cell::new(0),
+            alternative_count: Cell::new(0),
+            alternatives: RefCell::new(Vec::new()),
+        };
+    }
+
+    pub fn rule(&self) -> OpeningRule {
+        return self.rule;
+    }
+
+    pub fn phase(&self) -> OpeningPhase {
+        return self.phase.get();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        return self.phase.get() == OpFinished;
+    }
+
+    // who has to act during the opening, afterwards use get_player
+    // with the chess to move
+    pub fn current_player(&self) -> Player {
+        return self.actor.get();
+    }
+
+    pub fn get_player(&self, chess: ChessType) -> Player {
+        match chess {
+            CtBlack => return self.black_player.get(),
+            CtWhite => return self.black_player.get().get_other_player(),
+        }
+    }
+
+    pub fn get_chess(&self, player: Player) -> ChessType {
+        match player == self.black_player.get() {
+            true => return CtBlack,
+            false => return CtWhite,
+        }
+    }
+
+    // chess put on the board by the opening so far
+    pub fn chess_count(&self) -> usize {
+        return self.chess_count.get();
+    }
+
+    pub fn alternatives(&self) -> Vec<Coord> {
+        return self.alternatives.borrow().clone();
+    }
+
+    pub fn alternative_count(&self) -> usize {
+        return self.alternative_count.get();
+    }
+
+    pub fn legal_actions(&self) -> Vec<OpeningAction> {
+        match self.phase.get() {
+            OpPlaceChess(_) | OpPlaceAlternative => return vec![OaPlace],
+            OpChooseColor => return vec![OaChooseColor],
+            OpSwap2Choice => return vec![OaChooseColor, OaPlaceTwo],
+            OpDeclareAlternatives => return vec![OaDeclareAlternatives],
+            OpSelectAlternative => return vec![OaSelectAlternative],
+            OpTaraguchiFifth => return vec![OaPlace, OaOfferAlternatives],
+            OpFinished => return Vec::new(),
+        }
+    }
+
+    // the chess to put at coord, the caller puts it on the board
+    pub fn place(&self, coord: Coord) -> Result<ChessType, Error> {
+        let chess = match self.phase.get() {
+            OpPlaceChess(chess) => chess,
+            OpPlaceAlternative | OpTaraguchiFifth => CtBlack,
+            _ => return Err(self.invalid_action("can not put chess now")),
+        };
+
+        if let Some(area) = self.get_area() {
+            if !self.is_inside_area(coord, area) {
+                return Err(Error::create_with_detail(ErrorKind::CoordInvalid,
+                                                     "chess is outside of the opening area"));
+            }
+        }
+
+        // the offered fifth moves stay off the board until one is selected
+        if self.phase.get() == OpPlaceAlternative {
+            if self.alternatives.borrow().contains(&coord) {
+                return Err(Error::create_with_detail(ErrorKind::CellOccupied,
+                                                     "fifth move is already offered"));
+            }
+
+            self.alternatives.borrow_mut().push(coord);
+            if self.alternatives.borrow().len() == self.alternative_count.get() {
+                self.set_phase(OpSelectAlternative, self.get_player(CtWhite));
+            }
+            return Ok(chess);
+        }
+
+        let chess_count = self.chess_count.get() + 1;
+        self.chess_count.set(chess_count);
+
+        match (self.rule, self.phase.get()) {
+            (OrSwap, _) | (OrSwap2, _) | (OrSoosorv, _) if chess_count < 3 => {
+                self.phase.set(OpPlaceChess(chess.get_different_chess()));
+            },
+            (OrSwap, _) | (OrSoosorv, _) if chess_count == 3 => {
+                self.set_phase(OpChooseColor, PlSecond);
+            },
+            (OrSwap2, _) if chess_count == 3 => self.set_phase(OpSwap2Choice, PlSecond),
+            (OrSwap2, _) if chess_count == 4 => self.phase.set(OpPlaceChess(CtBlack)),
+            (OrSwap2, _) => self.set_phase(OpChooseColor, PlFirst),
+            (OrSoosorv, _) => {
+                self.set_phase(OpDeclareAlternatives, self.get_player(CtWhite));
+            },
+            (OrTaraguchi, _) => {
+                self.set_phase(OpChooseColor, self.actor.get().get_other_player());
+            },
+            (OrFree, _) | (OrSwap, _) => self.set_phase(OpFinished, self.get_player(CtWhite)),
+        }
+
+        return Ok(chess);
+    }
+
+    pub fn choose_color(&self, chess: ChessType) -> Result<(), Error> {
+        match self.phase.get() {
+            OpChooseColor | OpSwap2Choice => {},
+            _ => return Err(self.invalid_action("can not choose color now")),
+        }
+
+        match chess {
+            CtBlack => self.black_player.set(self.actor.get()),
+            CtWhite => self.black_player.set(self.actor.get().get_other_player()),
+        }
+
+        let chess_count = self.chess_count.get();
+        match self.rule {
+            OrSoosorv if chess_count == 3 => {
+                self.set_phase(OpPlaceChess(CtWhite), self.get_player(CtWhite));
+            },
+            OrSoosorv => self.set_phase(OpPlaceAlternative, self.get_player(CtBlack)),
+            OrTaraguchi if chess_count < 4 => {
+                let next_chess = match chess_count % 2 {
+                    0 => CtBlack,
+                    _ => CtWhite,
+                };
+                self.set_phase(OpPlaceChess(next_chess), self.get_player(next_chess));
+            },
+            OrTaraguchi if chess_count == 4 => {
+                self.set_phase(OpTaraguchiFifth, self.get_player(CtBlack));
+            },
+            _ => self.set_phase(OpFinished, self.get_player(CtWhite)),
+        }
+
+        return Ok(());
+    }
+
+    pub fn place_two(&self) -> Result<(), Error> {
+        if self.phase.get() != OpSwap2Choice {
+            return Err(self.invalid_action("can not put two more chess now"));
+        }
+
+        self.phase.set(OpPlaceChess(CtWhite));
+        return Ok(());
+    }
+
+    pub fn declare_alternatives(&self, count: usize) -> Result<(), Error> {
+        if self.phase.get() != OpDeclareAlternatives {
+            return Err(self.invalid_action("can not declare fifth moves now"));
+        } else if count == 0 || count > SOOSORV_MAX_ALTERNATIVES {
+            return Err(self.invalid_action("declared count of fifth moves out of range"));
+        }
+
+        self.alternative_count.set(count);
+        self.set_phase(OpChooseColor, self.actor.get().get_other_player());
+        return Ok(());
+    }
+
+    pub fn offer_alternatives(&self) -> Result<(), Error> {
+        if self.phase.get() != OpTaraguchiFifth {
+            return Err(self.invalid_action("can not offer fifth moves now"));
+        }
+
+        self.alternative_count.set(TARAGUCHI_ALTERNATIVES);
+        self.phase.set(OpPlaceAlternative);
+        return Ok(());
+    }
+
+    // keeps the chosen fifth move, returns the other ones to drop
+    pub fn select_alternative(&self, coord: Coord) -> Result<Vec<Coord>, Error> {
+        if self.phase.get() != OpSelectAlternative {
+            return Err(self.invalid_action("can not select fifth move now"));
+        } else if !self.alternatives.borrow().contains(&coord) {
+            return Err(Error::create_with_detail(ErrorKind::CoordInvalid,
+                                                 "coord is not an offered fifth move"));
+        }
+
+        let mut others = self.alternatives.borrow().clone();
+        others.retain(|alternative| *alternative != coord);
+        self.chess_count.set(self.chess_count.get() + 1);
+        self.alternatives.borrow_mut().clear();
+
+        self.set_phase(OpFinished, self.get_player(CtWhite));
+        return Ok(others);
+    }
+
+    // side of the square around the centre that the next chess must go in
+    fn get_area(&self) -> Option<usize> {
+        if self.rule != OrTaraguchi {
+            return None;
+        }
+
+        match self.phase.get() {
+            OpPlaceChess(_) | OpTaraguchiFifth => return Some(self.chess_count.get() * 2 + 1),
+            _ => return None,
+        }
+    }
+
+    fn is_inside_area(&self, coord: Coord, area: usize) -> bool {
+        let center = self.board_size / 2;
+        let radius = area / 2;
+        return coord.row + radius >= center && coord.row <= center + radius
+            && coord.col + radius >= center && coord.col <= center + radius;
+    }
+
+    fn set_phase(&self, phase: OpeningPhase, actor: Player) {
+        self.phase.set(phase);
+        self.actor.set(actor);
+    }
+
+    fn invalid_action(&self, message: &str) -> Error {
+        return Error::create_with_detail(ErrorKind::OpeningActionInvalid, message);
+    }
+}
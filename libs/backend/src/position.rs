@@ -0,0 +1,304 @@
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use board::*;
+use cross_point::*;
+use utils::*;
+
+use cross_point::ChessType::*;
+
+// a snapshot of the chess on a board and the side to move, the stones
+// are kept in row major order so that equal positions compare equal
+//
+// as a string the rows go from the top and are split by '/', a number
+// stands for that many empty cross points, 'x' for black and 'o' for
+// white, the board size comes first and the side to move last
+//
+// 3 3/1x1/o2 b
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Position {
+    board_size: usize,
+    stones: Vec<CoordAndChess>,
+    next_chess: ChessType,
+}
+
+impl Position {
+    pub fn create_with_stones(board_size: usize, stones: &[CoordAndChess],
+                              next_chess: ChessType) -> Result<Position, Error> {
+        // the packed position keeps the size in one byte
+        if board_size == 0 || board_size > u8::max_value() as usize {
+            return Err(invalid("invalid board size"));
+        }
+
+        let mut stones = stones.to_vec();
+        stones.sort_by_key(|stone| (stone.coord.row, stone.coord.col));
+        for (index, stone) in stones.iter().enumerate() {
+            if stone.coord.row >= board_size || stone.coord.col >= board_size {
+                return Err(invalid("stone out of the board"));
+            } else if index > 0 && stones[index - 1].coord == stone.coord {
+                return Err(invalid("two stones on one cross point"));
+            }
+        }
+
+        return Ok(Position { board_size, stones, next_chess });
+    }
+
+    pub fn create_with_board(board: &Board, next_chess: ChessType) -> Position {
+        let mut stones = Vec::new();
+        for row in 0..board.size() {
+            for col in 0..board.size() {
+                let coord = Coord{row, col};
+                if let Ok(chess) = board.get_chess_at(coord) {
+                    stones.push(CoordAndChess{coord, chess});
+                }
+            }
+        }
+
+        return Position { board_size: board.size(), stones, next_chess };
+    }
+
+    pub fn board_size(&self) -> usize {
+        return self.board_size;
+    }
+
+    pub fn stones(&self) -> &[CoordAndChess] {
+        return &self.stones;
+    }
+
+    pub fn next_chess(&self) -> ChessType {
+        return self.next_chess;
+    }
+
+    pub fn chess_at(&self, coord: Coord) -> Option<ChessType> {
+        return self.stones.iter().find(|stone| stone.coord == coord).map(|stone| stone.chess);
+    }
+
+    pub fn to_board(&self) -> Rc<Board> {
+        let board = Board::create_with_size(self.board_size);
+        for stone in self.stones.iter() {
+            board.put_chess_at(stone.coord, stone.chess).unwrap();
+        }
+
+        return board;
+    }
+
+    // the size, the side to move, and then a bitboard of black and one of
+    // white, each with a bit for every cross point in row major order
+    pub fn pack(&self) -> PackedPosition {
+        let bitboard_len = bitboard_len(self.board_size);
+        let mut bytes = vec![0; 2 + bitboard_len * 2];
+        bytes[0] = self.board_size as u8;
+        bytes[1] = match self.next_chess {
+            CtBlack => 0,
+            CtWhite => 1,
+        };
+
+        for stone in self.stones.iter() {
+            let index = stone.coord.row * self.board_size + stone.coord.col;
+            let offset = match stone.chess {
+                CtBlack => 2,
+                CtWhite => 2 + bitboard_len,
+            };
+            bytes[offset + index / 8] |= 1 << (index % 8);
+        }
+
+        return PackedPosition { bytes };
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows = Vec::new();
+        for row in 0..self.board_size {
+            let mut text = String::new();
+            let mut empty_count = 0;
+            for col in 0..self.board_size {
+                let chess = match self.chess_at(Coord{row, col}) {
+                    Some(chess) => chess,
+                    None => {
+                        empty_count += 1;
+                        continue;
+                    },
+                };
+
+                if empty_count > 0 {
+                    text.push_str(&empty_count.to_string());
+                    empty_count = 0;
+                }
+                text.push(chess_to_char(chess));
+            }
+            if empty_count > 0 {
+                text.push_str(&empty_count.to_string());
+            }
+            rows.push(text);
+        }
+
+        let side = match self.next_chess {
+            CtBlack => 'b',
+            CtWhite => 'w',
+        };
+        return write!(f, "{} {} {}", self.board_size, rows.join("/"), side);
+    }
+}
+
+impl FromStr for Position {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Position, Error> {
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        if fields.len() != 3 {
+            return Err(invalid("a position has the size, the rows and the side to move"));
+        }
+
+        let board_size: usize = fields[0].parse().map_err(|_| invalid("invalid board size"))?;
+        let rows: Vec<&str> = fields[1].split('/').collect();
+        if rows.len() != board_size {
+            return Err(invalid("the count of rows differs from the board size"));
+        }
+
+        let mut stones = Vec::new();
+        for (row, text) in rows.iter().enumerate() {
+            let mut col = 0;
+            let mut empty_count = String::new();
+            for c in text.chars().chain(Some('/')) {
+                if c.is_ascii_digit() {
+                    empty_count.push(c);
+                    continue;
+                }
+
+                // a count beyond the row is refused before adding it up
+                if !empty_count.is_empty() {
+                    match empty_count.parse::<usize>() {
+                        Ok(count) if count > 0 && count <= board_size - col => col += count,
+                        Ok(count) if count > 0 => {
+                            return Err(invalid("the length of a row differs from the board size"));
+                        },
+                        _ => return Err(invalid("invalid count of empty cross points")),
+                    }
+                    empty_count.clear();
+                }
+
+                match c {
+                    'x' | 'o' if col >= board_size => {
+                        return Err(invalid("the length of a row differs from the board size"));
+                    },
+                    'x' | 'o' => {
+                        stones.push(CoordAndChess{coord: Coord{row, col}, chess: char_to_chess(c)});
+                        col += 1;
+                    },
+                    '/' => {},
+                    _ => return Err(invalid("unexpected char in the rows")),
+                }
+            }
+
+            if col != board_size {
+                return Err(invalid("the length of a row differs from the board size"));
+            }
+        }
+
+        let next_chess = match fields[2] {
+            "b" => CtBlack,
+            "w" => CtWhite,
+            _ => return Err(invalid("the side to move is b or w")),
+        };
+        return Position::create_with_stones(board_size, &stones, next_chess);
+    }
+}
+
+// the bytes of Position::pack, written as hex digits
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct PackedPosition {
+    bytes: Vec<u8>,
+}
+
+impl PackedPosition {
+    pub fn create_with_bytes(bytes: Vec<u8>) -> PackedPosition {
+        return PackedPosition { bytes };
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        return &self.bytes;
+    }
+
+    pub fn unpack(&self) -> Result<Position, Error> {
+        let board_size = match self.bytes.first() {
+            Some(size) => *size as usize,
+            None => return Err(invalid("no bytes to unpack")),
+        };
+        let bitboard_len = bitboard_len(board_size);
+        if self.bytes.len() != 2 + bitboard_len * 2 {
+            return Err(invalid("the length of the bytes differs from the board size"));
+        }
+
+        let next_chess = match self.bytes[1] {
+            0 => CtBlack,
+            1 => CtWhite,
+            _ => return Err(invalid("invalid side to move")),
+        };
+
+        let mut stones = Vec::new();
+        for &(offset, chess) in [(2, CtBlack), (2 + bitboard_len, CtWhite)].iter() {
+            for index in 0..bitboard_len * 8 {
+                if self.bytes[offset + index / 8] & (1 << (index % 8)) == 0 {
+                    continue;
+                } else if index >= board_size * board_size {
+                    return Err(invalid("bit beyond the board"));
+                }
+
+                let coord = Coord{row: index / board_size, col: index % board_size};
+                stones.push(CoordAndChess{coord, chess});
+            }
+        }
+
+        return Position::create_with_stones(board_size, &stones, next_chess);
+    }
+}
+
+impl fmt::Display for PackedPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.bytes.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl FromStr for PackedPosition {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<PackedPosition, Error> {
+        let text = text.trim();
+        if text.len() % 2 != 0 || !text.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid("a packed position is written in pairs of hex digits"));
+        }
+
+        let bytes = (0..text.len() / 2)
+            .map(|index| u8::from_str_radix(&text[index * 2..index * 2 + 2], 16).unwrap())
+            .collect();
+        return Ok(PackedPosition { bytes });
+    }
+}
+
+fn bitboard_len(board_size: usize) -> usize {
+    return (board_size * board_size + 7) / 8;
+}
+
+fn chess_to_char(chess: ChessType) -> char {
+    match chess {
+        CtBlack => return 'x',
+        CtWhite => return 'o',
+    }
+}
+
+fn char_to_chess(c: char) -> ChessType {
+    match c {
+        'x' => return CtBlack,
+        _ => return CtWhite,
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    return Error::create_with_detail(ErrorKind::PositionInvalid, message);
+}
@@ -0,0 +1,100 @@
+use board::*;
+use utils::*;
+
+const PSQ_HEADER: &'static str = "Piskvorky";
+const PSQ_END: &'static str = "-1";
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct PsqMove {
+    pub coord: Coord,
+    pub time: u32,
+}
+
+// Piskvork game record, the header holds the board size and every move
+// follows as a 1 based "x,y,time" line, black moves first
+//
+// Piskvorky 15x15, 11:11, 0
+// 8,8,0
+// 9,8,0
+// -1
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct PsqRecord {
+    pub board_size: usize,
+    pub moves: Vec<PsqMove>,
+}
+
+impl PsqRecord {
+    pub fn create_with_coords(board_size: usize, coords: &[Coord]) -> PsqRecord {
+        let moves = coords.iter().map(|coord| PsqMove { coord: *coord, time: 0 }).collect();
+        return PsqRecord { board_size, moves };
+    }
+
+    pub fn coords(&self) -> Vec<Coord> {
+        return self.moves.iter().map(|psq_move| psq_move.coord).collect();
+    }
+
+    pub fn write(&self) -> String {
+        let mut text = format!("{} {}x{}, 11:11, 0\n", PSQ_HEADER, self.board_size, self.board_size);
+        for psq_move in self.moves.iter() {
+            text.push_str(&format!("{},{},{}\n", psq_move.coord.col + 1, psq_move.coord.row + 1,
+                                   psq_move.time));
+        }
+        text.push_str(PSQ_END);
+        text.push('\n');
+
+        return text;
+    }
+
+    // the moves end at the first line which is not a move, piskvork puts
+    // "-1" and the names of the engines there
+    pub fn parse(text: &str) -> Result<PsqRecord, Error> {
+        let mut lines = text.lines().map(|line| line.trim()).filter(|line| !line.is_empty());
+        let header = lines.next().ok_or_else(|| PsqRecord::invalid("empty record"))?;
+        let board_size = PsqRecord::parse_header(header)?;
+
+        let mut moves = Vec::new();
+        for line in lines {
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            if fields.len() != 3 {
+                break;
+            }
+
+            let numbers: Vec<i64> = match fields.iter().map(|field| field.parse()).collect() {
+                Ok(numbers) => numbers,
+                Err(_) => break,
+            };
+            if numbers[0] < 1 || numbers[1] < 1
+                || numbers[0] > board_size as i64 || numbers[1] > board_size as i64 {
+                return Err(PsqRecord::invalid("move out of the board"));
+            } else if numbers[2] < 0 {
+                return Err(PsqRecord::invalid("negative time of a move"));
+            }
+
+            let coord = Coord{row: numbers[1] as usize - 1, col: numbers[0] as usize - 1};
+            moves.push(PsqMove { coord, time: numbers[2] as u32 });
+        }
+
+        return Ok(PsqRecord { board_size, moves });
+    }
+
+    fn parse_header(header: &str) -> Result<usize, Error> {
+        if !header.starts_with(PSQ_HEADER) {
+            return Err(PsqRecord::invalid("missing piskvorky header"));
+        }
+
+        let size = header[PSQ_HEADER.len()..].trim().split(',').next().unwrap_or("");
+        let sizes: Vec<&str> = size.split('x').collect();
+        if sizes.len() != 2 || sizes[0] != sizes[1] {
+            return Err(PsqRecord::invalid("only square boards are supported"));
+        }
+
+        match sizes[0].parse::<usize>() {
+            Ok(board_size) if board_size > 0 => return Ok(board_size),
+            _ => return Err(PsqRecord::invalid("invalid board size")),
+        }
+    }
+
+    fn invalid(message: &str) -> Error {
+        return Error::create_with_detail(ErrorKind::RecordInvalid, message);
+    }
+}
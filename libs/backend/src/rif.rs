@@ -0,0 +1,227 @@
+use board::*;
+use notation::*;
+use utils::*;
+
+use self::RifRecordType::*;
+use self::XmlToken::*;
+
+// renjunet databases are always played on 15x15
+pub const RIF_BOARD_SIZE: usize = 15;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum RifRecordType {
+    RtGame,
+    RtProblem,
+}
+
+impl RifRecordType {
+    fn tag_name(&self) -> &'static str {
+        match *self {
+            RtGame => return "game",
+            RtProblem => return "problem",
+        }
+    }
+}
+
+// a <game> or <problem> element, attributes like id, black, white or
+// bresult are kept as they are and the <move> child becomes the moves
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct RifRecord {
+    pub record_type: RifRecordType,
+    pub attributes: Vec<(String, String)>,
+    pub moves: Vec<Coord>,
+}
+
+impl RifRecord {
+    pub fn create_with_moves(record_type: RifRecordType, moves: Vec<Coord>) -> RifRecord {
+        return RifRecord { record_type, attributes: Vec::new(), moves };
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        return self.attributes.iter()
+            .find(|attribute| attribute.0 == name)
+            .map(|attribute| attribute.1.as_str());
+    }
+
+    pub fn set_attribute(&mut self, name: &str, value: &str) {
+        match self.attributes.iter().position(|attribute| attribute.0 == name) {
+            Some(index) => self.attributes[index].1 = value.to_string(),
+            None => self.attributes.push((name.to_string(), value.to_string())),
+        }
+    }
+}
+
+// the renjunet RIF database, players and tournaments are skipped
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct RifDatabase {
+    pub records: Vec<RifRecord>,
+}
+
+impl RifDatabase {
+    pub fn new() -> RifDatabase {
+        return RifDatabase { records: Vec::new() };
+    }
+
+    pub fn records_of_type(&self, record_type: RifRecordType) -> Vec<&RifRecord> {
+        return self.records.iter().filter(|record| record.record_type == record_type).collect();
+    }
+
+    pub fn parse(text: &str) -> Result<RifDatabase, Error> {
+        let mut records = Vec::new();
+        let mut record: Option<RifRecord> = None;
+        let mut move_text: Option<String> = None;
+
+        for token in tokenize(text)? {
+            match token {
+                XtOpen(name, attributes, self_closing) => {
+                    let record_type = match name.as_str() {
+                        "game" => Some(RtGame),
+                        "problem" => Some(RtProblem),
+                        _ => None,
+                    };
+
+                    if let Some(record_type) = record_type {
+                        let new_record = RifRecord { record_type, attributes, moves: Vec::new() };
+                        match self_closing {
+                            true => records.push(new_record),
+                            false => record = Some(new_record),
+                        }
+                    } else if name == "move" && record.is_some() && !self_closing {
+                        move_text = Some(String::new());
+                    }
+                },
+                XtText(text) => {
+                    if let Some(ref mut move_text) = move_text {
+                        move_text.push_str(&text);
+                    }
+                },
+                XtClose(name) => {
+                    if name == "move" {
+                        if let (Some(text), Some(ref mut record)) = (move_text.take(), record.as_mut()) {
+                            record.moves = parse_moves(&text, RIF_BOARD_SIZE)?;
+                        }
+                    } else if name == "game" || name == "problem" {
+                        if let Some(record) = record.take() {
+                            records.push(record);
+                        }
+                    }
+                },
+            }
+        }
+
+        if record.is_some() {
+            return Err(invalid("unclosed game or problem"));
+        }
+
+        return Ok(RifDatabase { records });
+    }
+
+    pub fn write(&self) -> Result<String, Error> {
+        let mut text = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<database>\n");
+
+        for record_type in [RtGame, RtProblem].iter() {
+            let records = self.records_of_type(*record_type);
+            if records.is_empty() {
+                continue;
+            }
+
+            let tag_name = record_type.tag_name();
+            text.push_str(&format!("<{}s>\n", tag_name));
+            for record in records.iter() {
+                text.push_str(&format!("<{}", tag_name));
+                for &(ref name, ref value) in record.attributes.iter() {
+                    text.push_str(&format!(" {}=\"{}\"", name, escape(value)));
+                }
+                text.push_str(&format!(">\n<move>{}</move>\n</{}>\n",
+                                       write_moves(&record.moves, RIF_BOARD_SIZE)?, tag_name));
+            }
+            text.push_str(&format!("</{}s>\n", tag_name));
+        }
+
+        text.push_str("</database>\n");
+        return Ok(text);
+    }
+}
+
+enum XmlToken {
+    XtOpen(String, Vec<(String, String)>, bool),
+    XtClose(String),
+    XtText(String),
+}
+
+// just enough xml for rif files, declarations and comments are skipped
+fn tokenize(text: &str) -> Result<Vec<XmlToken>, Error> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(XtText(unescape(&rest[..end])));
+            rest = &rest[end..];
+            continue;
+        }
+
+        let (terminator, skipped) = if rest.starts_with("<!--") {
+            ("-->", true)
+        } else if rest.starts_with("<?") {
+            ("?>", true)
+        } else if rest.starts_with("<!") {
+            (">", true)
+        } else {
+            (">", false)
+        };
+        let end = rest.find(terminator).ok_or_else(|| invalid("unclosed tag"))?;
+        let tag = &rest[1..end];
+        rest = &rest[end + terminator.len()..];
+
+        if skipped {
+            continue;
+        } else if tag.starts_with('/') {
+            tokens.push(XtClose(tag[1..].trim().to_string()));
+        } else {
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+            let attributes = parse_attributes(&tag[name_end..])?;
+            tokens.push(XtOpen(tag[..name_end].to_string(), attributes, self_closing));
+        }
+    }
+
+    return Ok(tokens);
+}
+
+fn parse_attributes(text: &str) -> Result<Vec<(String, String)>, Error> {
+    let mut attributes = Vec::new();
+    let mut rest = text.trim_start();
+
+    while !rest.is_empty() {
+        let equal = rest.find('=').ok_or_else(|| invalid("attribute without value"))?;
+        let name = rest[..equal].trim().to_string();
+        rest = rest[equal + 1..].trim_start();
+
+        let quote = match rest.chars().next() {
+            Some(c) if c == '"' || c == '\'' => c,
+            _ => return Err(invalid("attribute value without quotes")),
+        };
+        let end = rest[1..].find(quote).ok_or_else(|| invalid("unclosed attribute value"))?;
+        attributes.push((name, unescape(&rest[1..end + 1])));
+        rest = rest[end + 2..].trim_start();
+    }
+
+    return Ok(attributes);
+}
+
+fn escape(text: &str) -> String {
+    return text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+        .replace('"', "&quot;").replace('\'', "&apos;");
+}
+
+fn unescape(text: &str) -> String {
+    return text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"")
+        .replace("&apos;", "'").replace("&amp;", "&");
+}
+
+fn invalid(message: &str) -> Error {
+    return Error::create_with_detail(ErrorKind::RecordInvalid, message);
+}
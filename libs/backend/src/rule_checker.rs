@@ -1,28 +1,61 @@
 use std::rc::*;
 use std::cell::*;
 use std::collections::HashMap;
-use slice_deque::SliceDeque;
 
 use evaluation_dfa::*;
+use forbidden_checker::*;
 use board::*;
 use tuple::*;
 use cross_point::*;
 use utils::*;
 
 use board::MoveDirection::*;
+use board::BoardEvent::*;
 use cross_point::CrossPointType::*;
 use cross_point::ChessType::*;
 
 use self::GameStatus::*;
-use self::MoveFailedType::*;
+use self::GameRule::*;
 use self::MoveResult::*;
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum GameStatus {
     GsGameOver(ChessType),
+    GsDraw,
     GsGameContinue,
 }
 
+// GrFreestyle: five or more wins
+// GrStandard: exactly five wins, six or more don't
+// GrRenju: exactly five for black, who also has forbidden moves, five or more for white
+// GrCaro: five or more wins, except a five with opponent chess at both ends
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum GameRule {
+    GrFreestyle,
+    GrStandard,
+    GrRenju,
+    GrCaro,
+}
+
+impl GameRule {
+    // length is the run of chess through the line, ends are the cross points
+    // just beyond it, None for the edge of the board which never blocks
+    pub fn is_winning_run(&self, chess: ChessType, length: usize,
+                          ends: [Option<CrossPointType>; 2]) -> bool {
+        if length < 5 {
+            return false;
+        }
+
+        let blocked = CptChess(chess.get_different_chess());
+        match *self {
+            GrFreestyle => return true,
+            GrStandard => return length == 5,
+            GrRenju => return chess == CtWhite || length == 5,
+            GrCaro => return length > 5 || ends[0] != Some(blocked) || ends[1] != Some(blocked),
+        }
+    }
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum MoveFailedType {
     MftBoarder,
@@ -35,38 +68,6 @@ pub enum MoveResult {
     MrFailed(MoveFailedType),
 }
 
-#[derive(Eq, PartialEq, Copy, Clone, Debug)]
-struct FirstVisitChess {
-    first_chess: Option<ChessType>,
-}
-
-impl FirstVisitChess {
-    pub fn new() -> FirstVisitChess {
-        return FirstVisitChess{first_chess: None};
-    }
-
-    pub fn set_chess(&mut self, chess: ChessType) {
-        match self.first_chess {
-            Some(_) => return,
-            None => self.first_chess = Some(chess),
-        }
-    }
-
-    pub fn set_cross_point(&mut self, cp: CrossPointType) {
-        match self.first_chess {
-            Some(_) => return,
-            None => match cp {
-                CptChess(chess) => self.first_chess = Some(chess),
-                CptEmpty => return,
-            }
-        }
-    }
-
-    pub fn get_first_chess(&self) -> Option<ChessType> {
-        return self.first_chess;
-    }
-}
-
 impl MoveResult {
     pub fn is_successful(&self) -> bool {
         match self {
@@ -79,10 +80,18 @@ impl MoveResult {
 
 pub struct RuleChecker {
     board: Rc<Board>,
+    rule: GameRule,
     status: Cell<GameStatus>,
+    winning_line: RefCell<Vec<Coord>>,
     score: Cell<i32>,
+    chess_count: Cell<usize>,
     tuples: RefCell<Vec<Tuple>>,
     tuple_indices: RefCell<HashMap<MoveDirection, usize>>,
+    tuples_at: RefCell<Vec<Vec<usize>>>,
+    tuple_chess_counts: RefCell<Vec<[usize; 2]>>,
+    open_tuple_count: Cell<usize>,
+    // draws as soon as no tuple is left open, not only on a full board
+    early_draw: Cell<bool>,
     evaluation_dfa: EvaluationDfa,
 }
 
@@ -96,49 +105,143 @@ impl BoardObserver for RuleChecker {
         for direction in check_directions.iter() {
             self.update_evaluation_by_event(direction, event);
         }
+
+        self.update_tuple_counts_by_event(event);
+        self.update_winning_line_by_event(&check_directions, event);
+        self.update_game_status();
     }
 }
 
 impl RuleChecker {
     pub fn create_with_detail(board: Rc<Board>) -> Rc<RuleChecker> {
+        return RuleChecker::create_with_rule(board, GrFreestyle);
+    }
+
+    pub fn create_with_rule(board: Rc<Board>, rule: GameRule) -> Rc<RuleChecker> {
+        return RuleChecker::create_with_evaluation(board, rule, EvaluationDfa::new());
+    }
+
+    pub fn create_with_evaluation(board: Rc<Board>, rule: GameRule,
+                                  evaluation_dfa: EvaluationDfa) -> Rc<RuleChecker> {
         let rule_checker = Rc::new(RuleChecker {
             board: board.clone(),
+            rule,
             status: Cell::new(GsGameContinue),
+            winning_line: RefCell::new(Vec::new()),
             score: Cell::new(0),
+            chess_count: Cell::new(0),
             tuples: RefCell::new(Vec::new()),
             tuple_indices: RefCell::new(HashMap::new()),
-            evaluation_dfa: EvaluationDfa::new(),
+            tuples_at: RefCell::new(Vec::new()),
+            tuple_chess_counts: RefCell::new(Vec::new()),
+            open_tuple_count: Cell::new(0),
+            early_draw: Cell::new(false),
+            evaluation_dfa,
         });
 
         rule_checker.set_all_tuples();
+        rule_checker.count_tuples();
+        rule_checker.score.set(rule_checker.evaluate_board());
+        rule_checker.scan_winning_line();
+        rule_checker.update_game_status();
         board.add_observers(Rc::downgrade(&rule_checker));
         return rule_checker;
     }
 
+    // the status is kept up to date on every board event
     pub fn check_game_status(&self) -> GameStatus {
-        for tuple in self.tuples.borrow().iter() {
-
-            if tuple.count(CptChess(CtBlack)) == 5 {
-                self.status.set(GsGameOver(CtBlack));
-                return self.status.get();
-            } else if tuple.count(CptChess(CtWhite)) == 5 {
-                self.status.set(GsGameOver(CtWhite));
-                return self.status.get();
-            }
+        return self.status.get();
+    }
+
+    pub fn rule(&self) -> GameRule {
+        return self.rule;
+    }
+
+    // only black has forbidden moves, and only under renju
+    pub fn check_forbidden(&self, coord: Coord, chess: ChessType) -> Option<ForbiddenType> {
+        if self.rule != GrRenju || chess != CtBlack {
+            return None;
+        }
+
+        return ForbiddenChecker::create_with_board(&self.board).check(coord);
+    }
+
+    pub fn put_chess_at(&self, coord: Coord, chess: ChessType) -> Result<(), Error> {
+        if self.status.get() != GsGameContinue {
+            return Err(Error::create_with_detail(ErrorKind::GameAlreadyOver,
+                                                 "the game is already over"));
+        } else if let Some(forbidden) = self.check_forbidden(coord, chess) {
+            return Err(Error::create_with_detail(ErrorKind::MoveForbidden(forbidden),
+                                                 "forbidden move for black"));
         }
 
-        return GsGameContinue;
+        return self.board.put_chess_at(coord, chess);
     }
 
     pub fn game_status(&self) -> GameStatus {
         return self.status.get();
     }
 
+    // the run of chess that won the game, ordered along its line
+    pub fn winning_line(&self) -> Option<Vec<Coord>> {
+        match self.status.get() {
+            GsGameOver(_) => return Some(self.winning_line.borrow().clone()),
+            GsDraw | GsGameContinue => return None,
+        }
+    }
+
+    pub fn is_early_draw(&self) -> bool {
+        return self.early_draw.get();
+    }
+
+    // off by default, so that the game only ends in a draw on a full board
+    pub fn set_early_draw(&self, early_draw: bool) {
+        self.early_draw.set(early_draw);
+        self.update_game_status();
+    }
+
+    // lines of five cross points that still hold chess of one color at most,
+    // nobody can make five any more once there are none left
+    pub fn open_tuple_count(&self) -> usize {
+        return self.open_tuple_count.get();
+    }
+
+    // black minus white, kept up to date on every board event
     pub fn get_evaluation(&self) -> i32 {
         return self.score.get();
     }
 
-    pub fn get_simple_play(&self) -> Coord {
+    // evaluation of the whole board from scratch, line by line
+    pub fn evaluate_board(&self) -> i32 {
+        let size = self.board.size();
+        let mut starts = Vec::new();
+        for i in 0..size {
+            starts.push((Coord{row: i, col: 0}, MdRight));
+            starts.push((Coord{row: 0, col: i}, MdDown));
+            starts.push((Coord{row: i, col: 0}, MdDownRight));
+            starts.push((Coord{row: 0, col: i}, MdDownLeft));
+            if i > 0 {
+                starts.push((Coord{row: 0, col: i}, MdDownRight));
+                starts.push((Coord{row: i, col: size - 1}, MdDownLeft));
+            }
+        }
+
+        let mut score = 0;
+        for &(coord, md) in starts.iter() {
+            let mut coord_md = coord;
+            let mut cpts = vec![self.board.get_cross_point_type_at(coord)];
+            while let Ok(coord_n) = self.board.move_by_coord(coord_md, md) {
+                coord_md = coord_n;
+                cpts.push(self.board.get_cross_point_type_at(coord_n));
+            }
+            score += self.evaluation_dfa.evaluate_cross_points(&cpts);
+        }
+
+        return score;
+    }
+
+    // None once the board is full
+    pub fn get_simple_play(&self) -> Option<Coord> {
         let mut tuple_score = Vec::new();
         let mut cp_score = Vec::new();
         tuple_score.resize(self.tuples.borrow().len(), 0);
@@ -164,22 +267,22 @@ impl RuleChecker {
             }
         }
 
-        let mut max_score = 0; let mut max_index = 0;
+        let mut max_score = 0; let mut max_index = None;
         for index in 0..cp_score.len() {
             if !self.board.have_chess_at(cp_score[index].1) {
-                if cp_score[index].0 > max_score {
+                if max_index.is_none() || cp_score[index].0 > max_score {
                     max_score = cp_score[index].0;
-                    max_index = index;
+                    max_index = Some(index);
                 }
             }
         }
 
-        return cp_score[max_index].1;
+        return max_index.map(|index| cp_score[index].1);
     }
 
     fn set_all_tuples(&self) {
         let board_cp_count = self.board.size();
-        let board_tp_count = board_cp_count - 5;
+        let board_tp_count = board_cp_count - 4;
         let mut tuple_indices_ref = self.tuple_indices.borrow_mut();
 
         tuple_indices_ref.insert(MdRight, self.tuples.borrow().len());
@@ -199,6 +302,43 @@ impl RuleChecker {
                         Coord{row: board_tp_count, col: board_tp_count});
     }
 
+    fn count_tuples(&self) {
+        let tuples = self.tuples.borrow();
+        let mut tuples_at = vec![Vec::new(); self.board.cp_count()];
+        let mut tuple_chess_counts = Vec::with_capacity(tuples.len());
+        let mut open_tuple_count = 0;
+
+        for i in 0..tuples.len() {
+            let tuple = &tuples[i];
+            for index in 0..5 {
+                if let Ok(coord) = tuple.coord_at(index) {
+                    tuples_at[self.board.coord_to_index(coord)].push(i);
+                }
+            }
+
+            let counts = [tuple.count(CptChess(CtBlack)) as usize,
+                tuple.count(CptChess(CtWhite)) as usize];
+            if counts[0] == 0 || counts[1] == 0 {
+                open_tuple_count += 1;
+            }
+            tuple_chess_counts.push(counts);
+        }
+
+        let mut chess_count = 0;
+        for row in 0..self.board.size() {
+            for col in 0..self.board.size() {
+                if self.board.have_chess_at(Coord{row, col}) {
+                    chess_count += 1;
+                }
+            }
+        }
+
+        *self.tuples_at.borrow_mut() = tuples_at;
+        *self.tuple_chess_counts.borrow_mut() = tuple_chess_counts;
+        self.open_tuple_count.set(open_tuple_count);
+        self.chess_count.set(chess_count);
+    }
+
     fn set_tuples(&self, md: MoveDirection, offset: Coord, count: Coord) {
         let end = offset + count;
 
@@ -210,137 +350,207 @@ impl RuleChecker {
         }
     }
 
-    // move by cross point linked list
-    // create a struct include SliceDeque and calculate index when push front
     fn update_evaluation_by_event(&self, md: &[MoveDirection], event: BoardEvent) {
-        let coord = event.get_coord(); let chess = event.get_chess();
-        let mut coord_md = [coord, coord];
-        let mut first_chess = [FirstVisitChess::new(), FirstVisitChess::new()];
-        let mut continue_flag = [true, true];
-        let mut count = 1; let mut i = 1; let max_count = 7; let mut index = 0;
-        let mut cpts = SliceDeque::with_capacity(max_count); cpts.push_back(CptChess(chess));
-
-        while count < max_count && (continue_flag[0] || continue_flag[1]){
-            i = (i + 1) % 2;
-            if continue_flag[i] == true {
-                count += 1;
-                match self.move_to(CoordAndChess { coord: coord_md[i], chess }, md[i]) {
-                    MrSuccessful(coord_and_cross_point) => {
-                        first_chess[i].set_cross_point(coord_and_cross_point.cross_point);
-                        coord_md[i] = coord_and_cross_point.coord;
-                        match i == 0 {
-                            true => {
-                                cpts.push_front(coord_and_cross_point.cross_point);
-                                index += 1;
-                            },
-                            false => cpts.push_back(coord_and_cross_point.cross_point),
-                        }
+        let coord = event.get_coord();
+        let mut index = 0;
+        let mut cpts = Vec::with_capacity(EVALUATION_RADIUS * 2 + 1);
+        cpts.push(self.board.get_cross_point_type_at(coord));
+
+        // the slice stops at the edge of the board, which the dfa reads as blocked,
+        // the first direction is walked backward and turned round before the second
+        for i in 0..2 {
+            let mut coord_md = coord;
+            for _count in 0..EVALUATION_RADIUS {
+                match self.board.move_by_coord(coord_md, md[i]) {
+                    Ok(coord_n) => {
+                        coord_md = coord_n;
+                        cpts.push(self.board.get_cross_point_type_at(coord_n));
                     },
-                    MrFailed(move_failed_type) => match move_failed_type {
-                        MftDifferenceChess => {
-                            first_chess[i].set_chess(chess.get_different_chess());
-                            continue_flag[i] = false;
-                            match i == 0 {
-                                true => {
-                                    cpts.push_front(CptChess(chess.get_different_chess()));
-                                    index += 1;
-                                },
-                                false => cpts.push_back(CptChess(chess.get_different_chess())),
-                            }
-                        },
-                        MftBoarder => {
-                            continue_flag[i] = false;
-                            match i == 0 {
-                                true => {
-                                    cpts.push_front(CptChess(chess.get_different_chess()));
-                                    index += 1;
-                                },
-                                false => cpts.push_back(CptChess(chess.get_different_chess())),
-                            }
-                        },
-                    }
+                    Err(_) => break,
                 }
             }
-        }
 
-        /*
-        println!("mds: {:?}", md);
-        println!("coords: {:?}", coord_md);
-        println!("first_chess: {:?}", first_chess);
-        println!("=================================");
-        for cpt in cpts.iter() {
-            println!("{:?}", cpt);
+            if i == 0 {
+                index = cpts.len() - 1;
+                cpts.reverse();
+            }
         }
-        println!("=================================");
-        println!("chess is {:?}", chess);
-        */
 
         let score = self.score.get();
         self.score.set(score + self.evaluation_dfa
             .evaluate_event(cpts.as_mut_slice(), index, event));
-        for i in 0..2 {
-            match first_chess[i].get_first_chess() {
-                Some(chess_f) => match chess_f == chess {
-                    true => {},
-                    false => self.update_tuple_evaluation(
-                        CoordAndChess{coord, chess: chess_f},
-                        md[i], event
-                    ),
-                },
-                None => {},
-            }
-        }
     }
 
-    fn update_tuple_evaluation(&self, coord_and_chess: CoordAndChess,
-                               md: MoveDirection, event: BoardEvent) {
-        let coord = coord_and_chess.coord; let chess = coord_and_chess.chess;
-        let mut coord_md = coord;
-        let mut count = 1; let max_count = 7;
-        let mut cpts = SliceDeque::with_capacity(max_count);
-        cpts.push_back(CptChess(chess.get_different_chess()));
-
-        while count < max_count {
-            count += 1;
-            match self.move_to(CoordAndChess { coord: coord_md, chess }, md) {
-                MrSuccessful(coord_and_cross_point) => {
-                    coord_md = coord_and_cross_point.coord;
-                    cpts.push_back(coord_and_cross_point.cross_point);
-                },
-                MrFailed(_) => {
-                    cpts.push_back(CptChess(chess.get_different_chess()));
-                    break;
-                },
+    fn update_tuple_counts_by_event(&self, event: BoardEvent) {
+        let chess_index = match event.get_chess() {
+            CtBlack => 0,
+            CtWhite => 1,
+        };
+        let is_put = match event {
+            BePutChess(_) => true,
+            BeRemoveChess(_) => false,
+        };
+
+        match is_put {
+            true => self.chess_count.set(self.chess_count.get() + 1),
+            false => self.chess_count.set(self.chess_count.get() - 1),
+        }
+
+        let index = self.board.coord_to_index(event.get_coord());
+        let mut tuple_chess_counts = self.tuple_chess_counts.borrow_mut();
+        for i in self.tuples_at.borrow()[index].iter() {
+            let counts = &mut tuple_chess_counts[*i];
+            let was_open = counts[0] == 0 || counts[1] == 0;
+            match is_put {
+                true => counts[chess_index] += 1,
+                false => counts[chess_index] -= 1,
+            }
+            let is_open = counts[0] == 0 || counts[1] == 0;
+
+            match (was_open, is_open) {
+                (true, false) => self.open_tuple_count.set(self.open_tuple_count.get() - 1),
+                (false, true) => self.open_tuple_count.set(self.open_tuple_count.get() + 1),
+                _ => {},
             }
         }
+    }
+
+    // a win goes first, the game is drawn when the board is full, or with
+    // the early draw when no tuple is left open for either color
+    fn update_game_status(&self) {
+        let winner = self.winning_line.borrow().first()
+            .and_then(|coord| self.board.get_chess_at(*coord).ok());
+        let status = match winner {
+            Some(chess) => GsGameOver(chess),
+            None => match self.chess_count.get() == self.board.cp_count()
+                || (self.early_draw.get() && self.open_tuple_count.get() == 0) {
+                true => GsDraw,
+                false => GsGameContinue,
+            },
+        };
+
+        self.status.set(status);
+    }
+
+    // a put chess can only win through its own four lines, a removed one can
+    // end the win it was part of, or let a neighbour's run win by shortening
+    // an overline or opening a blocked end, so only those lines are checked,
+    // the whole board is only scanned again when a win is spoiled
+    fn update_winning_line_by_event(&self, check_directions: &[[MoveDirection; 2]],
+                                    event: BoardEvent) {
+        match event {
+            BePutChess(coord_and_chess) => {
+                let coord = coord_and_chess.coord;
+                if !self.winning_line.borrow().is_empty() {
+                    // a chess next to the winning line may turn it into an
+                    // overline or block both of its ends
+                    let line = self.winning_line.borrow().clone();
+                    let touched = line.iter().any(|coord_l| {
+                        (coord_l.row as i32 - coord.row as i32).abs() <= 1
+                            && (coord_l.col as i32 - coord.col as i32).abs() <= 1
+                    });
+                    if touched {
+                        self.winning_line.borrow_mut().clear();
+                        self.scan_winning_line();
+                    }
+                    return;
+                }
+
+                for direction in check_directions.iter() {
+                    if self.check_winning_run(coord, direction) {
+                        return;
+                    }
+                }
+            },
+            BeRemoveChess(coord_and_chess) => {
+                let coord = coord_and_chess.coord;
+                let game_over = !self.winning_line.borrow().is_empty();
+                if game_over {
+                    if !self.winning_line.borrow().contains(&coord) {
+                        return;
+                    }
+                    self.winning_line.borrow_mut().clear();
+                }
+
+                for direction in check_directions.iter() {
+                    for md in direction.iter() {
+                        if let Ok(coord_n) = self.board.move_by_coord(coord, *md) {
+                            if self.board.have_chess_at(coord_n)
+                                && self.check_winning_run(coord_n, direction) {
+                                return;
+                            }
+                        }
+                    }
+                }
 
-        /*
-        println!("=================================");
-        for cpt in cpts.iter() {
-            println!("{:?}", cpt);
+                // the removed chess ended its win, but another one may be
+                // left elsewhere on the board
+                if game_over {
+                    self.scan_winning_line();
+                }
+            },
         }
-        println!("=================================");
-        println!("chess is {:?}", chess.get_different_chess());
-        */
+    }
 
-        let score = self.score.get();
-        self.score.set(score + self.evaluation_dfa.evaluate_event(cpts.as_mut_slice(), 0, event));
+    // only needed when the board already holds chess, afterwards every
+    // event updates the status incrementally
+    fn scan_winning_line(&self) {
+        let check_directions = [
+            [MdLeft, MdRight], [MdUp, MdDown],
+            [MdUpLeft, MdDownRight], [MdUpRight, MdDownLeft]
+        ];
+
+        for row in 0..self.board.size() {
+            for col in 0..self.board.size() {
+                let coord = Coord{row, col};
+                if !self.board.have_chess_at(coord) {
+                    continue;
+                }
+
+                for direction in check_directions.iter() {
+                    if self.check_winning_run(coord, direction) {
+                        return;
+                    }
+                }
+            }
+        }
     }
 
-    fn move_to(&self, coord_and_chess: CoordAndChess, md: MoveDirection) -> MoveResult {
-        match self.board.move_by_coord(coord_and_chess.coord, md) {
-            Ok(coord) => match self.board.get_cross_point_type_at(coord) {
-                CptEmpty => return MrSuccessful(CoordAndCrossPoint{coord, cross_point: CptEmpty}),
-                CptChess(chess) => match chess == coord_and_chess.chess {
-                    false => return MrFailed(MftDifferenceChess),
-                    _ => return MrSuccessful(CoordAndCrossPoint{coord, cross_point: CptChess(chess)}),
+    // the run through coord along the line, it is recorded as the winning
+    // line when the rule takes it as a win
+    fn check_winning_run(&self, coord: Coord, direction: &[MoveDirection; 2]) -> bool {
+        let chess = match self.board.get_chess_at(coord) {
+            Ok(chess) => chess,
+            Err(_) => return false,
+        };
+        let mut line = vec![coord];
+        let mut ends = [None, None];
+
+        for i in 0..2 {
+            let mut coord_md = coord;
+            while let Ok(coord_n) = self.board.move_by_coord(coord_md, direction[i]) {
+                let cross_point = self.board.get_cross_point_type_at(coord_n);
+                if cross_point != CptChess(chess) {
+                    ends[i] = Some(cross_point);
+                    break;
                 }
+
+                coord_md = coord_n;
+                line.push(coord_n);
             }
-            Err(error) => match error.kind {
-                ErrorKind::CoordInvalid => return MrFailed(MftBoarder),
-                //_ => panic!("RuleChecker move failed with error {:?}", error.message),
+
+            // the first direction is walked backward
+            if i == 0 {
+                line.reverse();
             }
         }
+
+        if !self.rule.is_winning_run(chess, line.len(), ends) {
+            return false;
+        }
+
+        *self.winning_line.borrow_mut() = line;
+        return true;
     }
 
     fn get_tuple_score(&self, index: usize) -> i32 {
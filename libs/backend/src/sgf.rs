@@ -0,0 +1,376 @@
+use board::*;
+use cross_point::*;
+use rule_checker::*;
+use utils::*;
+
+use cross_point::ChessType::*;
+use rule_checker::GameRule::*;
+
+// GM[4] is gomoku and renju in the sgf game list
+const SGF_GAME_GOMOKU: &'static str = "4";
+const SGF_DEFAULT_BOARD_SIZE: usize = 15;
+// the coords are two letters from 'a' to 'z'
+const SGF_MAX_BOARD_SIZE: usize = 26;
+
+// a node of the game tree, the first child goes on with the main line
+// and the others are variations of it
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct SgfNode {
+    pub step: Option<CoordAndChess>,
+    pub comment: Option<String>,
+    pub children: Vec<SgfNode>,
+}
+
+impl SgfNode {
+    pub fn new() -> SgfNode {
+        return SgfNode { step: None, comment: None, children: Vec::new() };
+    }
+
+    pub fn create_with_step(step: CoordAndChess) -> SgfNode {
+        return SgfNode { step: Some(step), comment: None, children: Vec::new() };
+    }
+
+    // the steps from this node on, always following the first child
+    pub fn main_line(&self) -> Vec<CoordAndChess> {
+        let mut steps = Vec::new();
+        let mut node = Some(self);
+        while let Some(current) = node {
+            if let Some(step) = current.step {
+                steps.push(step);
+            }
+            node = current.children.first();
+        }
+
+        return steps;
+    }
+}
+
+// Smart Game Format record of one gomoku game, the root node holds the
+// size and the rule, the chess of a set up position with AB and AW and
+// the side to move first with PL, some programs put the first move
+// there as well
+//
+// (;GM[4]FF[4]CA[UTF-8]SZ[15]RU[Renju];B[hh];W[ig](;B[ii])(;B[gg]C[variation]))
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct SgfRecord {
+    pub board_size: usize,
+    pub rule: GameRule,
+    pub setup: Vec<CoordAndChess>,
+    pub first_chess: ChessType,
+    pub root: SgfNode,
+}
+
+impl SgfRecord {
+    pub fn create_with_steps(board_size: usize, rule: GameRule,
+                             steps: &[CoordAndChess]) -> SgfRecord {
+        let mut line: Option<SgfNode> = None;
+        for step in steps.iter().rev() {
+            let mut node = SgfNode::create_with_step(*step);
+            node.children.extend(line.take());
+            line = Some(node);
+        }
+
+        let mut root = SgfNode::new();
+        root.children.extend(line);
+        return SgfRecord { board_size, rule, setup: Vec::new(), first_chess: CtBlack, root };
+    }
+
+    pub fn main_line(&self) -> Vec<CoordAndChess> {
+        return self.root.main_line();
+    }
+
+    pub fn write(&self) -> String {
+        let mut text = format!("(;GM[{}]FF[4]CA[UTF-8]SZ[{}]RU[{}]", SGF_GAME_GOMOKU,
+                               self.board_size, rule_to_str(self.rule));
+        for &(name, chess) in [("AB", CtBlack), ("AW", CtWhite)].iter() {
+            let stones: Vec<&CoordAndChess> = self.setup.iter()
+                .filter(|step| step.chess == chess).collect();
+            if !stones.is_empty() {
+                text.push_str(name);
+                for step in stones.iter() {
+                    text.push_str(&format!("[{}]", coord_to_sgf(step.coord)));
+                }
+            }
+        }
+        if !self.setup.is_empty() || self.first_chess != CtBlack {
+            text.push_str(&format!("PL[{}]", chess_to_sgf(self.first_chess)));
+        }
+        write_properties(&self.root, &mut text);
+        write_children(&self.root, &mut text);
+        text.push_str(")\n");
+
+        return text;
+    }
+
+    // only the first game of a collection is read, properties which have
+    // nothing to do with the moves are skipped
+    pub fn parse(text: &str) -> Result<SgfRecord, Error> {
+        let mut parser = SgfParser { chars: text.chars().collect(), pos: 0 };
+        let tree = parser.parse_game_tree()?;
+
+        if let Some(game) = property(&tree.properties, "GM") {
+            if game != SGF_GAME_GOMOKU {
+                return Err(invalid("not a gomoku record"));
+            }
+        }
+
+        let board_size = match property(&tree.properties, "SZ") {
+            Some(size) => match size.parse::<usize>() {
+                Ok(size) if size > 0 && size <= SGF_MAX_BOARD_SIZE => size,
+                _ => return Err(invalid("invalid board size")),
+            },
+            None => SGF_DEFAULT_BOARD_SIZE,
+        };
+        let rule = property(&tree.properties, "RU").map_or(GrFreestyle, rule_from_str);
+
+        let mut setup = Vec::new();
+        for &(name, chess) in [("AB", CtBlack), ("AW", CtWhite)].iter() {
+            if let Some(property) = tree.properties.iter().find(|property| property.0 == name) {
+                for value in property.1.iter() {
+                    setup.push(CoordAndChess{coord: sgf_coord(value, board_size)?, chess});
+                }
+            }
+        }
+        let first_chess = match property(&tree.properties, "PL") {
+            Some("W") | Some("w") => CtWhite,
+            Some("B") | Some("b") | None => CtBlack,
+            Some(_) => return Err(invalid("invalid side to move")),
+        };
+        let root = tree.to_node(board_size)?;
+
+        return Ok(SgfRecord { board_size, rule, setup, first_chess, root });
+    }
+}
+
+fn write_node(node: &SgfNode, text: &mut String) {
+    text.push(';');
+    write_properties(node, text);
+    write_children(node, text);
+}
+
+fn write_properties(node: &SgfNode, text: &mut String) {
+    if let Some(step) = node.step {
+        text.push_str(&format!("{}[{}]", chess_to_sgf(step.chess), coord_to_sgf(step.coord)));
+    }
+
+    if let Some(ref comment) = node.comment {
+        text.push_str(&format!("C[{}]", escape(comment)));
+    }
+}
+
+fn write_children(node: &SgfNode, text: &mut String) {
+    if node.children.len() == 1 {
+        write_node(&node.children[0], text);
+        return;
+    }
+
+    for child in node.children.iter() {
+        text.push('(');
+        write_node(child, text);
+        text.push(')');
+    }
+}
+
+fn chess_to_sgf(chess: ChessType) -> &'static str {
+    match chess {
+        CtBlack => return "B",
+        CtWhite => return "W",
+    }
+}
+
+fn coord_to_sgf(coord: Coord) -> String {
+    return format!("{}{}", (b'a' + coord.col as u8) as char, (b'a' + coord.row as u8) as char);
+}
+
+fn rule_to_str(rule: GameRule) -> &'static str {
+    match rule {
+        GrFreestyle => return "Freestyle",
+        GrStandard => return "Standard",
+        GrRenju => return "Renju",
+        GrCaro => return "Caro",
+    }
+}
+
+// other programs write all kinds of rule names, those unknown here are
+// played as freestyle
+fn rule_from_str(rule: &str) -> GameRule {
+    match rule.trim().to_lowercase().as_str() {
+        "standard" => return GrStandard,
+        "renju" => return GrRenju,
+        "caro" => return GrCaro,
+        _ => return GrFreestyle,
+    }
+}
+
+fn escape(text: &str) -> String {
+    return text.replace('\\', "\\\\").replace(']', "\\]");
+}
+
+fn property<'a>(properties: &'a [(String, Vec<String>)], name: &str) -> Option<&'a str> {
+    return properties.iter()
+        .find(|property| property.0 == name)
+        .map(|property| property.1[0].as_str());
+}
+
+fn invalid(message: &str) -> Error {
+    return Error::create_with_detail(ErrorKind::RecordInvalid, message);
+}
+
+// the properties of a node as they are in the text, turned into a node
+// once the board size is known from the root
+struct SgfTree {
+    properties: Vec<(String, Vec<String>)>,
+    children: Vec<SgfTree>,
+}
+
+impl SgfTree {
+    fn to_node(&self, board_size: usize) -> Result<SgfNode, Error> {
+        let black = property(&self.properties, "B");
+        let white = property(&self.properties, "W");
+        let step = match (black, white) {
+            (Some(_), Some(_)) => return Err(invalid("both colors move in one node")),
+            (Some(value), None) => Some(CoordAndChess{coord: sgf_coord(value, board_size)?,
+                                                      chess: CtBlack}),
+            (None, Some(value)) => Some(CoordAndChess{coord: sgf_coord(value, board_size)?,
+                                                      chess: CtWhite}),
+            (None, None) => None,
+        };
+
+        let mut children = Vec::new();
+        for child in self.children.iter() {
+            children.push(child.to_node(board_size)?);
+        }
+
+        let comment = property(&self.properties, "C").map(|comment| comment.to_string());
+        return Ok(SgfNode { step, comment, children });
+    }
+}
+
+// a column letter and then a row letter, both from the top left
+fn sgf_coord(value: &str, board_size: usize) -> Result<Coord, Error> {
+    let letters: Vec<char> = value.trim().chars().collect();
+    if letters.len() != 2 {
+        return Err(invalid("pass or invalid move"));
+    }
+
+    let mut indices = [0; 2];
+    for (index, letter) in letters.iter().enumerate() {
+        if !letter.is_ascii_lowercase() || (*letter as usize - 'a' as usize) >= board_size {
+            return Err(invalid("move out of the board"));
+        }
+        indices[index] = *letter as usize - 'a' as usize;
+    }
+
+    return Ok(Coord{row: indices[1], col: indices[0]});
+}
+
+struct SgfParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl SgfParser {
+    // the next char which is not a white space, '\u{feff}' is the
+    // byte order mark some editors put first
+    fn peek(&mut self) -> Option<char> {
+        while let Some(c) = self.chars.get(self.pos).cloned() {
+            if !c.is_whitespace() && c != '\u{feff}' {
+                return Some(c);
+            }
+            self.pos += 1;
+        }
+
+        return None;
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        if self.peek() != Some(expected) {
+            return Err(invalid(&format!("'{}' expected", expected)));
+        }
+
+        self.pos += 1;
+        return Ok(());
+    }
+
+    // "(" the nodes of a line one after another, then the variations ")"
+    fn parse_game_tree(&mut self) -> Result<SgfTree, Error> {
+        self.expect('(')?;
+        let mut sequence = Vec::new();
+        while self.peek() == Some(';') {
+            self.pos += 1;
+            sequence.push(self.parse_properties()?);
+        }
+
+        let mut children = Vec::new();
+        while self.peek() == Some('(') {
+            children.push(self.parse_game_tree()?);
+        }
+        self.expect(')')?;
+
+        let mut tree = match sequence.pop() {
+            Some(properties) => SgfTree { properties, children },
+            None => return Err(invalid("game tree without nodes")),
+        };
+        while let Some(properties) = sequence.pop() {
+            tree = SgfTree { properties, children: vec![tree] };
+        }
+
+        return Ok(tree);
+    }
+
+    fn parse_properties(&mut self) -> Result<Vec<(String, Vec<String>)>, Error> {
+        let mut properties = Vec::new();
+
+        while self.peek().map_or(false, |c| c.is_ascii_alphabetic()) {
+            // old files write names like "AddBlack", only the capitals count
+            let mut name = String::new();
+            while let Some(c) = self.chars.get(self.pos).cloned() {
+                if !c.is_ascii_alphabetic() {
+                    break;
+                }
+                if c.is_ascii_uppercase() {
+                    name.push(c);
+                }
+                self.pos += 1;
+            }
+
+            let mut values = Vec::new();
+            while self.peek() == Some('[') {
+                self.pos += 1;
+                values.push(self.parse_value()?);
+            }
+            if values.is_empty() {
+                return Err(invalid("property without value"));
+            }
+
+            properties.push((name, values));
+        }
+
+        return Ok(properties);
+    }
+
+    fn parse_value(&mut self) -> Result<String, Error> {
+        let mut value = String::new();
+
+        loop {
+            let c = self.next_char()?;
+            match c {
+                ']' => return Ok(value),
+                '\\' => {
+                    // an escaped line break is a soft one and left out
+                    let escaped = self.next_char()?;
+                    if escaped != '\n' && escaped != '\r' {
+                        value.push(escaped);
+                    }
+                },
+                _ => value.push(c),
+            }
+        }
+    }
+
+    fn next_char(&mut self) -> Result<char, Error> {
+        let c = self.chars.get(self.pos).cloned().ok_or_else(|| invalid("unclosed property value"))?;
+        self.pos += 1;
+        return Ok(c);
+    }
+}
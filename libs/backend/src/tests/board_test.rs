@@ -1,6 +1,7 @@
 use super::super::board::*;
 use super::super::board::MoveDirection::*;
 use super::super::cross_point::*;
+use super::super::utils::*;
 
 #[test]
 fn size() {
@@ -38,14 +39,63 @@ fn chess() {
     for i in 0..3 {
         for j in 0..3 {
             assert_eq!(b.have_chess_at(Coord{row: i, col: j}), false);
-            b.put_chess_at(Coord{row: i, col: j}, ChessType::CtBlack);
+            b.put_chess_at(Coord{row: i, col: j}, ChessType::CtBlack).unwrap();
             assert_eq!(b.have_chess_at(Coord{row: i, col: j}), true);
-            assert_eq!(b.get_chess_at(Coord{row: i, col: j}), ChessType::CtBlack);
-            b.remove_chess_at(Coord{row: i, col: j});
+            assert_eq!(b.get_chess_at(Coord{row: i, col: j}), Ok(ChessType::CtBlack));
+            b.remove_chess_at(Coord{row: i, col: j}).unwrap();
             assert_eq!(b.have_chess_at(Coord{row: i, col: j}), false);
-            b.put_chess_at(Coord{row: i, col: j}, ChessType::CtWhite);
+            b.put_chess_at(Coord{row: i, col: j}, ChessType::CtWhite).unwrap();
             assert_eq!(b.have_chess_at(Coord{row: i, col: j}), true);
-            assert_eq!(b.get_chess_at(Coord{row: i, col: j}), ChessType::CtWhite);
+            assert_eq!(b.get_chess_at(Coord{row: i, col: j}), Ok(ChessType::CtWhite));
+        }
+    }
+}
+
+#[test]
+fn chess_error() {
+    let b = Board::create_with_size(3);
+    let coord = Coord{row: 1, col: 1};
+
+    assert_eq!(b.get_chess_at(coord).unwrap_err().kind, ErrorKind::CellEmpty);
+    assert_eq!(b.remove_chess_at(coord).unwrap_err().kind, ErrorKind::CellEmpty);
+    b.put_chess_at(coord, ChessType::CtBlack).unwrap();
+    assert_eq!(b.put_chess_at(coord, ChessType::CtWhite).unwrap_err().kind,
+               ErrorKind::CellOccupied);
+    assert_eq!(b.get_chess_at(coord), Ok(ChessType::CtBlack));
+
+    let coord_invalid = Coord{row: 3, col: 0};
+    assert_eq!(b.get_chess_at(coord_invalid).unwrap_err().kind, ErrorKind::CoordInvalid);
+    assert_eq!(b.put_chess_at(coord_invalid, ChessType::CtBlack).unwrap_err().kind,
+               ErrorKind::CoordInvalid);
+    assert_eq!(b.remove_chess_at(coord_invalid).unwrap_err().kind, ErrorKind::CoordInvalid);
+    assert_eq!(b.move_by_coord(coord_invalid, MdUp).unwrap_err().kind, ErrorKind::CoordInvalid);
+}
+
+#[test]
+fn notation() {
+    assert_eq!(Coord{row: 7, col: 7}.to_notation(15), Ok("h8".to_string()));
+    assert_eq!(Coord{row: 14, col: 0}.to_notation(15), Ok("a1".to_string()));
+    assert_eq!(Coord{row: 0, col: 14}.to_notation(15), Ok("o15".to_string()));
+    assert_eq!(Coord{row: 0, col: 0}.to_notation(19), Ok("a19".to_string()));
+    assert_eq!(Coord{row: 25, col: 25}.to_notation(26), Ok("z1".to_string()));
+
+    for coord in [Coord{row: 20, col: 3}, Coord{row: 15, col: 0}, Coord{row: 0, col: 15}].iter() {
+        assert_eq!(coord.to_notation(15).unwrap_err().kind, ErrorKind::CoordInvalid);
+    }
+    assert_eq!(Coord{row: 0, col: 26}.to_notation(30).unwrap_err().kind, ErrorKind::CoordInvalid);
+
+    assert_eq!(Coord::from_notation("h8", 15), Ok(Coord{row: 7, col: 7}));
+    assert_eq!(Coord::from_notation(" O15 ", 15), Ok(Coord{row: 0, col: 14}));
+    assert_eq!(Coord::from_notation("a1", 15), Ok(Coord{row: 14, col: 0}));
+
+    for notation in ["", "h", "8", "p8", "h0", "h16", "hh8", "h8x"].iter() {
+        assert_eq!(Coord::from_notation(notation, 15).unwrap_err().kind, ErrorKind::CoordInvalid);
+    }
+
+    for row in 0..15 {
+        for col in 0..15 {
+            let coord = Coord{row, col};
+            assert_eq!(Coord::from_notation(&coord.to_notation(15).unwrap(), 15), Ok(coord));
         }
     }
 }
@@ -1,4 +1,5 @@
 use super::super::cross_point::*;
+use super::super::utils::*;
 
 #[test]
 fn get_and_set() {
@@ -7,51 +8,49 @@ fn get_and_set() {
 
     let cp = CrossPoint::create_with_chess(ChessType::CtBlack);
     assert_eq!(cp.have_chess(), true);
-    assert_eq!(cp.get_chess(), ChessType::CtBlack);
+    assert_eq!(cp.get_chess(), Ok(ChessType::CtBlack));
     assert_eq!(cp.get_cross_point_type(), CrossPointType::CptChess(ChessType::CtBlack));
-    cp.remove_chess();
+    assert_eq!(cp.remove_chess(), Ok(ChessType::CtBlack));
     assert_eq!(cp.have_chess(), false);
-    cp.put_chess(ChessType::CtWhite);
+    assert_eq!(cp.put_chess(ChessType::CtWhite), Ok(()));
     assert_eq!(cp.have_chess(), true);
-    assert_eq!(cp.get_chess(), ChessType::CtWhite);
+    assert_eq!(cp.get_chess(), Ok(ChessType::CtWhite));
     assert_eq!(cp.get_cross_point_type(), CrossPointType::CptChess(ChessType::CtWhite));
 
     let cp = CrossPoint::create_with_chess(ChessType::CtWhite);
     assert_eq!(cp.have_chess(), true);
-    assert_eq!(cp.get_chess(), ChessType::CtWhite);
+    assert_eq!(cp.get_chess(), Ok(ChessType::CtWhite));
     assert_eq!(cp.get_cross_point_type(), CrossPointType::CptChess(ChessType::CtWhite));
-    cp.remove_chess();
+    assert_eq!(cp.remove_chess(), Ok(ChessType::CtWhite));
     assert_eq!(cp.have_chess(), false);
-    cp.put_chess(ChessType::CtBlack);
+    assert_eq!(cp.put_chess(ChessType::CtBlack), Ok(()));
     assert_eq!(cp.have_chess(), true);
-    assert_eq!(cp.get_chess(), ChessType::CtBlack);
+    assert_eq!(cp.get_chess(), Ok(ChessType::CtBlack));
     assert_eq!(cp.get_cross_point_type(), CrossPointType::CptChess(ChessType::CtBlack));
 }
 
 #[test]
-#[should_panic]
-fn get_chess_panic() {
+fn get_chess_error() {
     let cp = CrossPoint::new();
-    cp.get_chess();
+    assert_eq!(cp.get_chess().unwrap_err().kind, ErrorKind::CellEmpty);
 }
 
 #[test]
-#[should_panic]
-fn remove_chess_panic() {
+fn remove_chess_error() {
     let cp = CrossPoint::new();
-    cp.remove_chess();
+    assert_eq!(cp.remove_chess().unwrap_err().kind, ErrorKind::CellEmpty);
 }
 
 #[test]
-#[should_panic]
-fn put_chess_panic_1() {
+fn put_chess_error_1() {
     let cp = CrossPoint::create_with_chess(ChessType::CtBlack);
-    cp.put_chess(ChessType::CtWhite);
+    assert_eq!(cp.put_chess(ChessType::CtWhite).unwrap_err().kind, ErrorKind::CellOccupied);
+    assert_eq!(cp.get_chess(), Ok(ChessType::CtBlack));
 }
 
 #[test]
-#[should_panic]
-fn put_chess_panic_2() {
+fn put_chess_error_2() {
     let cp = CrossPoint::create_with_chess(ChessType::CtWhite);
-    cp.put_chess(ChessType::CtBlack);
+    assert_eq!(cp.put_chess(ChessType::CtBlack).unwrap_err().kind, ErrorKind::CellOccupied);
+    assert_eq!(cp.get_chess(), Ok(ChessType::CtWhite));
 }
@@ -0,0 +1,103 @@
+use super::super::cross_point::*;
+use super::super::board::*;
+use super::super::evaluation_dfa::*;
+
+use super::super::cross_point::ChessType::*;
+use super::super::cross_point::CrossPointType::*;
+use super::super::board::BoardEvent::*;
+use super::super::evaluation_dfa::ShapeType::*;
+
+fn cross_points_from_str(line: &str) -> Vec<CrossPointType> {
+    return line.chars().map(|c| match c {
+        'X' => CptChess(CtBlack),
+        'O' => CptChess(CtWhite),
+        _ => CptEmpty,
+    }).collect();
+}
+
+fn classify(line: &str) -> Vec<ShapeType> {
+    let dfa = EvaluationDfa::new();
+    return dfa.classify_tuple(&cross_points_from_str(line), CtBlack);
+}
+
+#[test]
+fn classify_tuple() {
+    assert_eq!(classify("___X___"), vec![]);
+    assert_eq!(classify("XXXXX"), vec![StFive]);
+    assert_eq!(classify("__XXXXX__"), vec![StFive]);
+    assert_eq!(classify("__XXXX__"), vec![StOpenFour]);
+    assert_eq!(classify("XXXX__"), vec![StClosedFour]);
+    assert_eq!(classify("_OXXXX_"), vec![StClosedFour]);
+    assert_eq!(classify("__XX_XX__"), vec![StClosedFour]);
+    assert_eq!(classify("_X_XXX_"), vec![StClosedFour]);
+    assert_eq!(classify("O_XXX__"), vec![StOpenThree]);
+    assert_eq!(classify("__XXX__"), vec![StOpenThree]);
+    assert_eq!(classify("__X_X__"), vec![StOpenTwo]);
+    assert_eq!(classify("__XX_X__"), vec![StBrokenThree]);
+    assert_eq!(classify("OXXX___"), vec![StClosedThree]);
+    assert_eq!(classify("O_XXX_O"), vec![StClosedThree]);
+    assert_eq!(classify("OX_X_XO"), vec![StClosedThree]);
+    assert_eq!(classify("___XX___"), vec![StOpenTwo]);
+    assert_eq!(classify("__X__X__"), vec![StOpenTwo]);
+    assert_eq!(classify("OXX____"), vec![StClosedTwo]);
+    assert_eq!(classify("O_XX__O"), vec![StClosedTwo]);
+    assert_eq!(classify("OXXXXO"), vec![]);
+    assert_eq!(classify("_OOOO_"), vec![]);
+}
+
+#[test]
+fn both_colors() {
+    let dfa = EvaluationDfa::new();
+    let weights = dfa.weights();
+    let cpts = cross_points_from_str("OXXXX__OOOX_");
+
+    assert_eq!(dfa.classify_tuple(&cpts, CtBlack), vec![StClosedFour]);
+    assert_eq!(dfa.classify_tuple(&cpts, CtWhite), vec![StClosedThree]);
+    assert_eq!(dfa.evaluate_tuple(&cpts, CtBlack), weights.closed_four);
+    assert_eq!(dfa.evaluate_tuple(&cpts, CtWhite), weights.closed_three);
+    assert_eq!(dfa.evaluate_cross_points(&cpts), weights.closed_four - weights.closed_three);
+}
+
+#[test]
+fn weights() {
+    let mut weights = EvaluationWeights::new();
+    weights.five = 0;
+    weights.open_four = 0;
+    weights.closed_four = 0;
+    weights.open_three = 1;
+    weights.broken_three = 10;
+    weights.closed_three = 0;
+    weights.open_two = 0;
+    weights.closed_two = 0;
+
+    let dfa = EvaluationDfa::create_with_weights(weights);
+    assert_eq!(dfa.weights(), weights);
+    assert_eq!(dfa.evaluate_tuple(&cross_points_from_str("__XXX__"), CtBlack), 1);
+    assert_eq!(dfa.evaluate_tuple(&cross_points_from_str("__XXX__X_XX__"), CtBlack), 11);
+    assert_eq!(dfa.evaluate_tuple(&cross_points_from_str("__XXX__"), CtWhite), 0);
+}
+
+#[test]
+fn evaluate_event() {
+    let dfa = EvaluationDfa::new();
+    let weights = dfa.weights();
+    let coord = Coord{row: 0, col: 0};
+
+    let mut cpts = cross_points_from_str("__XXXX__");
+    let event = BePutChess(CoordAndChess{coord, chess: CtBlack});
+    assert_eq!(dfa.evaluate_event(&mut cpts, 2, event), weights.open_four - weights.open_three);
+
+    // the three can still be made an open four on the other side
+    let mut cpts = cross_points_from_str("__XXX_O__");
+    let event = BePutChess(CoordAndChess{coord, chess: CtWhite});
+    assert_eq!(dfa.evaluate_event(&mut cpts, 6, event), 0);
+
+    let mut cpts = cross_points_from_str("__XXXO__");
+    let event = BePutChess(CoordAndChess{coord, chess: CtWhite});
+    assert_eq!(dfa.evaluate_event(&mut cpts, 5, event), weights.closed_three - weights.open_three);
+
+    let mut cpts = cross_points_from_str("_XX_XX_");
+    let event = BeRemoveChess(CoordAndChess{coord, chess: CtBlack});
+    assert_eq!(dfa.evaluate_event(&mut cpts, 3, event),
+               weights.closed_four - weights.five);
+}
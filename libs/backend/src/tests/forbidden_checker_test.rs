@@ -0,0 +1,172 @@
+use super::super::board::*;
+use super::super::forbidden_checker::*;
+use super::super::rule_checker::*;
+use super::super::utils::*;
+
+use super::super::cross_point::ChessType::*;
+use super::super::forbidden_checker::ForbiddenType::*;
+use super::super::rule_checker::GameRule::*;
+
+fn board_with_black(coords: &[(usize, usize)]) -> ::std::rc::Rc<Board> {
+    let board = Board::new();
+    for &(row, col) in coords.iter() {
+        board.put_chess_at(Coord{row, col}, CtBlack).unwrap();
+    }
+
+    return board;
+}
+
+fn check(board: &Board, row: usize, col: usize) -> Option<ForbiddenType> {
+    return ForbiddenChecker::create_with_board(board).check(Coord{row, col});
+}
+
+#[test]
+fn double_three() {
+    let board = board_with_black(&[(7, 5), (7, 6), (5, 7), (6, 7)]);        // . . X . .
+    assert_eq!(check(&board, 7, 7), Some(FtDoubleThree));                  // . . X . .
+                                                                           // X X * . .
+    let board = board_with_black(&[(7, 4), (7, 6), (5, 9), (6, 8)]);
+    assert_eq!(check(&board, 7, 7), Some(FtDoubleThree));
+
+    let board = board_with_black(&[(7, 5), (7, 6), (5, 7)]);
+    assert_eq!(check(&board, 7, 7), None);
+}
+
+#[test]
+fn blocked_three() {
+    let board = board_with_black(&[(7, 5), (7, 6), (5, 7), (6, 7)]);
+    board.put_chess_at(Coord{row: 4, col: 7}, CtWhite).unwrap();                     // . . O . .
+    assert_eq!(check(&board, 7, 7), None);                                  // . . X . .
+                                                                            // . . X . .
+    let board = board_with_black(&[(7, 5), (7, 6), (5, 7), (6, 7)]);       // X X * . .
+    board.put_chess_at(Coord{row: 7, col: 8}, CtWhite).unwrap();
+    board.put_chess_at(Coord{row: 7, col: 3}, CtWhite).unwrap();
+    assert_eq!(check(&board, 7, 7), None);
+}
+
+#[test]
+fn false_three() {
+    // the straight four points of the row three are both overlines
+    let board = board_with_black(&[
+        (7, 5), (7, 6), (5, 7), (6, 7),
+        (5, 8), (6, 8), (8, 8), (9, 8), (10, 8),
+        (5, 4), (6, 4), (8, 4), (9, 4), (10, 4),
+    ]);
+    assert_eq!(check(&board, 7, 8), Some(FtOverline));
+    assert_eq!(check(&board, 7, 4), Some(FtOverline));
+    assert_eq!(check(&board, 7, 7), None);
+}
+
+// the row three only becomes a straight four at (7, 8), which is a four-four
+#[test]
+fn false_three_by_forbidden_point() {
+    let board = board_with_black(&[(7, 5), (7, 6), (5, 7), (6, 7), (4, 8), (5, 8), (6, 8)]);
+    board.put_chess_at(Coord{row: 7, col: 3}, CtWhite).unwrap();            // . . . . . X .
+    assert_eq!(check(&board, 7, 7), None);                                  // . . . . X X .
+                                                                            // . . . . X X .
+                                                                            // O . X X * . .
+    let board = board_with_black(&[(7, 5), (7, 6), (5, 7), (6, 7), (5, 8), (6, 8)]);
+    board.put_chess_at(Coord{row: 7, col: 3}, CtWhite).unwrap();
+    assert_eq!(check(&board, 7, 7), Some(FtDoubleThree));
+}
+
+// the row three at (7, 7) goes on at (7, 8), which is a four-three-three
+// while the diagonal three through it is real, and only a four-three once
+// that three is false because (9, 10) is an overline
+#[test]
+fn nested_false_three() {
+    let stones = [(7, 5), (7, 6), (5, 7), (6, 7), (5, 8), (6, 8), (8, 9)];
+    let overline = [(12, 7), (11, 8), (10, 9), (8, 11), (7, 12)];
+
+    let board = board_with_black(&stones);
+    board.put_chess_at(Coord{row: 7, col: 3}, CtWhite).unwrap();
+    board.put_chess_at(Coord{row: 4, col: 5}, CtWhite).unwrap();
+    assert_eq!(check(&board, 7, 7), None);
+
+    for &(row, col) in overline.iter() {
+        board.put_chess_at(Coord{row, col}, CtBlack).unwrap();
+    }
+    assert_eq!(check(&board, 9, 10), Some(FtOverline));
+    assert_eq!(check(&board, 7, 7), Some(FtDoubleThree));
+}
+
+#[test]
+fn double_four() {
+    let board = board_with_black(&[(7, 4), (7, 5), (7, 6), (4, 7), (5, 7), (6, 7)]);
+    board.put_chess_at(Coord{row: 7, col: 3}, CtWhite).unwrap();
+    board.put_chess_at(Coord{row: 3, col: 7}, CtWhite).unwrap();
+    assert_eq!(check(&board, 7, 7), Some(FtDoubleFour));
+
+    // two fours on one line, X _ X * X _ X
+    let board = board_with_black(&[(7, 3), (7, 5), (7, 7), (7, 9)]);
+    assert_eq!(check(&board, 7, 6), Some(FtDoubleFour));
+
+    // X X X _ * _ X X X
+    let board = board_with_black(&[(7, 2), (7, 3), (7, 4), (7, 8), (7, 9), (7, 10)]);
+    assert_eq!(check(&board, 7, 6), Some(FtDoubleFour));
+
+    // a straight four is only one four
+    let board = board_with_black(&[(7, 4), (7, 5), (7, 6)]);
+    assert_eq!(check(&board, 7, 7), None);
+}
+
+#[test]
+fn four_three() {
+    let board = board_with_black(&[(7, 4), (7, 5), (7, 6), (5, 7), (6, 7)]);
+    board.put_chess_at(Coord{row: 7, col: 3}, CtWhite).unwrap();
+    assert_eq!(check(&board, 7, 7), None);
+}
+
+#[test]
+fn overline() {
+    let board = board_with_black(&[(7, 2), (7, 3), (7, 4), (7, 6), (7, 7)]);
+    assert_eq!(check(&board, 7, 5), Some(FtOverline));
+
+    let board = board_with_black(&[(2, 2), (3, 3), (4, 4), (5, 5), (7, 7), (8, 8)]);
+    assert_eq!(check(&board, 6, 6), Some(FtOverline));
+}
+
+#[test]
+fn five_wins() {
+    // five on the row while also making a double three
+    let board = board_with_black(&[(7, 3), (7, 4), (7, 5), (7, 6), (5, 9), (6, 8),
+                                   (5, 7), (6, 7)]);
+    assert_eq!(check(&board, 7, 7), None);
+
+    // X X X X * _ X is an exact five, X X X X * X is an overline
+    let board = board_with_black(&[(7, 2), (7, 3), (7, 4), (7, 5), (7, 8)]);
+    assert_eq!(check(&board, 7, 6), None);
+    let board = board_with_black(&[(7, 2), (7, 3), (7, 4), (7, 5), (7, 7)]);
+    assert_eq!(check(&board, 7, 6), Some(FtOverline));
+
+    // five on the row while the column is an overline
+    let board = board_with_black(&[(7, 3), (7, 4), (7, 5), (7, 6),
+                                   (4, 7), (5, 7), (6, 7), (8, 7), (9, 7)]);
+    assert_eq!(check(&board, 7, 7), None);
+}
+
+#[test]
+fn occupied_and_out_of_board() {
+    let board = board_with_black(&[(7, 5), (7, 6), (5, 7), (6, 7)]);
+    assert_eq!(check(&board, 7, 6), None);
+    assert_eq!(check(&board, 15, 7), None);
+}
+
+#[test]
+fn renju_rule_checker() {
+    let board = board_with_black(&[(7, 5), (7, 6), (5, 7), (6, 7)]);
+    let freestyle = RuleChecker::create_with_detail(board.clone());
+    let renju = RuleChecker::create_with_rule(board.clone(), GrRenju);
+    assert_eq!(freestyle.rule(), GrFreestyle);
+    assert_eq!(renju.rule(), GrRenju);
+
+    assert_eq!(freestyle.check_forbidden(Coord{row: 7, col: 7}, CtBlack), None);
+    assert_eq!(renju.check_forbidden(Coord{row: 7, col: 7}, CtWhite), None);
+    assert_eq!(renju.check_forbidden(Coord{row: 7, col: 7}, CtBlack), Some(FtDoubleThree));
+
+    assert_eq!(renju.put_chess_at(Coord{row: 7, col: 7}, CtBlack).unwrap_err().kind,
+               ErrorKind::MoveForbidden(FtDoubleThree));
+    assert_eq!(board.have_chess_at(Coord{row: 7, col: 7}), false);
+    assert_eq!(renju.put_chess_at(Coord{row: 7, col: 7}, CtWhite), Ok(()));
+    assert_eq!(board.have_chess_at(Coord{row: 7, col: 7}), true);
+}
@@ -0,0 +1,219 @@
+use std::env;
+use std::fs;
+
+use super::super::board::*;
+use super::super::game_session::*;
+use super::super::utils::*;
+
+use super::super::cross_point::ChessType::*;
+use super::super::game_session::RecordFormat::*;
+use super::super::opening::OpeningAction::*;
+use super::super::opening::OpeningPhase::*;
+use super::super::opening::OpeningRule::*;
+use super::super::opening::Player::*;
+use super::super::rule_checker::GameRule::*;
+
+fn coord(row: usize, col: usize) -> Coord {
+    return Coord{row, col};
+}
+
+#[test]
+fn record_format() {
+    assert_eq!(RecordFormat::from_path("game.sgf"), RfSgf);
+    assert_eq!(RecordFormat::from_path("GAME.PSQ"), RfPsq);
+    assert_eq!(RecordFormat::from_path("openings.rif"), RfRif);
+    assert_eq!(RecordFormat::from_path("game.txt"), RfNotation);
+}
+
+#[test]
+fn put_and_take_back() {
+    let session = GameSession::new();
+    assert_eq!(session.next_chess(), CtBlack);
+
+    session.put_chess(coord(7, 7)).unwrap();
+    let before = session.steps();
+    session.put_chess(coord(7, 8)).unwrap();
+    assert_eq!(session.next_chess(), CtBlack);
+    assert_eq!(session.changed_cells(&before),
+               vec![CellChange{coord: coord(7, 8), chess: Some(CtWhite)}]);
+
+    let before = session.steps();
+    assert_eq!(session.take_back().unwrap().map(|step| step.coord), Some(coord(7, 8)));
+    assert_eq!(session.changed_cells(&before), vec![CellChange{coord: coord(7, 8), chess: None}]);
+    assert_eq!(session.board().have_chess_at(coord(7, 8)), false);
+
+    session.put_chess(coord(8, 8)).unwrap();
+    assert_eq!(session.take_back().unwrap().map(|step| step.coord), Some(coord(8, 8)));
+    assert_eq!(session.variation_count(), 2);
+    assert_eq!(session.forward_move().unwrap().map(|step| step.coord), Some(coord(8, 8)));
+    assert_eq!(session.next_variation().unwrap(), true);
+    assert_eq!(session.last_step().map(|step| step.coord), Some(coord(7, 8)));
+
+    session.undo_to_my_last_move(CtBlack).unwrap();
+    assert_eq!(session.steps().len(), 0);
+    assert_eq!(session.take_back().unwrap(), None);
+}
+
+#[test]
+fn winner() {
+    let session = GameSession::create_with_size(9, GrFreestyle);
+    assert_eq!(session.board_size(), 9);
+
+    for col in 0..4 {
+        session.put_chess(coord(4, col)).unwrap();
+        session.put_chess(coord(5, col)).unwrap();
+    }
+    assert_eq!(session.is_over(), false);
+    session.put_chess(coord(4, 4)).unwrap();
+
+    assert_eq!(session.is_over(), true);
+    assert_eq!(session.winner(), Some(CtBlack));
+    assert_eq!(session.winning_line().len(), 5);
+    assert_eq!(session.put_chess(coord(5, 4)).unwrap_err().kind, ErrorKind::GameAlreadyOver);
+
+    session.restart().unwrap();
+    assert_eq!(session.is_over(), false);
+    assert_eq!(session.steps().len(), 0);
+}
+
+#[test]
+fn jump_to_move() {
+    let session = GameSession::new();
+    for &(row, col) in [(7, 7), (7, 8), (8, 8)].iter() {
+        session.put_chess(coord(row, col)).unwrap();
+    }
+
+    session.jump_to_move(1).unwrap();
+    assert_eq!(session.steps().len(), 1);
+    assert_eq!(session.next_chess(), CtWhite);
+    session.jump_to_move(3).unwrap();
+    assert_eq!(session.last_step().map(|step| step.coord), Some(coord(8, 8)));
+}
+
+#[test]
+fn setup() {
+    let session = GameSession::new();
+    session.put_chess(coord(7, 7)).unwrap();
+    session.begin_setup().unwrap();
+    assert_eq!(session.is_in_setup(), true);
+    assert_eq!(session.steps().len(), 0);
+    assert_eq!(session.setup_stones(), vec![CoordAndChess{coord: coord(7, 7), chess: CtBlack}]);
+    assert_eq!(session.board().have_chess_at(coord(7, 7)), true);
+
+    session.setup_chess(coord(7, 8), Some(CtWhite)).unwrap();
+    session.setup_chess(coord(8, 8), Some(CtBlack)).unwrap();
+    session.setup_chess(coord(8, 8), Some(CtWhite)).unwrap();
+    session.setup_chess(coord(8, 8), None).unwrap();
+    assert_eq!(session.board().have_chess_at(coord(8, 8)), false);
+
+    assert_eq!(session.finish_setup(CtWhite).unwrap_err().kind, ErrorKind::PositionInvalid);
+    assert_eq!(session.finish_setup(CtBlack).is_ok(), true);
+    assert_eq!(session.is_in_setup(), false);
+    assert_eq!(session.setup_stones().len(), 2);
+    assert_eq!(session.next_chess(), CtBlack);
+
+    // the set up chess are not moves to take back
+    assert_eq!(session.take_back().unwrap(), None);
+}
+
+#[test]
+fn swap_opening() {
+    let session = GameSession::new();
+    session.set_opening_rule(OrSwap).unwrap();
+    assert_eq!(session.is_opening_finished(), false);
+    assert_eq!(session.put_chess(coord(7, 7)).unwrap_err().kind, ErrorKind::OpeningActionInvalid);
+
+    assert_eq!(session.put_opening_chess(coord(7, 7)), Ok(CtBlack));
+    assert_eq!(session.put_opening_chess(coord(7, 8)), Ok(CtWhite));
+    assert_eq!(session.put_opening_chess(coord(8, 8)), Ok(CtBlack));
+    assert_eq!(session.put_opening_chess(coord(8, 8)).unwrap_err().kind, ErrorKind::CellOccupied);
+
+    let status = session.opening_status();
+    assert_eq!(status.phase, OpChooseColor);
+    assert_eq!(status.player, PlSecond);
+    assert_eq!(status.actions, vec![OaChooseColor]);
+
+    session.choose_color(CtBlack).unwrap();
+    assert_eq!(session.is_opening_finished(), true);
+    assert_eq!(session.next_chess(), CtWhite);
+    assert_eq!(session.opening_status().player, PlFirst);
+
+    // the opening chess stay on the board
+    assert_eq!(session.take_back().unwrap(), None);
+    session.put_chess(coord(9, 9)).unwrap();
+    assert_eq!(session.take_back().unwrap().map(|step| step.coord), Some(coord(9, 9)));
+}
+
+// a chess the game refuses leaves the opening where it was
+#[test]
+fn refused_opening_chess() {
+    let session = GameSession::new();
+    session.set_opening_rule(OrSwap).unwrap();
+    session.game().begin_setup().unwrap();
+
+    assert_eq!(session.put_opening_chess(coord(7, 7)).unwrap_err().kind, ErrorKind::GameNotStarted);
+    assert_eq!(session.opening_status().phase, OpPlaceChess(CtBlack));
+    assert_eq!(session.next_chess(), CtBlack);
+
+    session.game().finish_setup(CtBlack).unwrap();
+    assert_eq!(session.put_opening_chess(coord(7, 7)), Ok(CtBlack));
+    assert_eq!(session.opening_status().phase, OpPlaceChess(CtWhite));
+}
+
+// the offered fifth moves are only marks, even lying in one line
+#[test]
+fn offered_alternatives() {
+    let session = GameSession::new();
+    session.set_opening_rule(OrTaraguchi).unwrap();
+    for &(row, col) in [(7, 7), (6, 7), (8, 8), (4, 4)].iter() {
+        session.put_opening_chess(coord(row, col)).unwrap();
+        session.choose_color(CtBlack).unwrap();
+    }
+    session.offer_alternatives().unwrap();
+    let before = session.steps();
+
+    for col in 0..10 {
+        assert_eq!(session.put_opening_chess(coord(0, col)), Ok(CtBlack));
+    }
+    assert_eq!(session.is_over(), false);
+    assert_eq!(session.steps(), before);
+    assert_eq!(session.board().have_chess_at(coord(0, 0)), false);
+
+    let status = session.opening_status();
+    assert_eq!(status.phase, OpSelectAlternative);
+    assert_eq!(status.alternatives.len(), 10);
+
+    assert_eq!(session.select_alternative(coord(1, 0)).unwrap_err().kind, ErrorKind::CoordInvalid);
+    assert_eq!(session.select_alternative(coord(0, 3)).unwrap().len(), 9);
+    assert_eq!(session.is_opening_finished(), true);
+    assert_eq!(session.opening_status().alternatives, vec![]);
+    assert_eq!(session.changed_cells(&before),
+               vec![CellChange{coord: coord(0, 3), chess: Some(CtBlack)}]);
+    assert_eq!(session.next_chess(), CtWhite);
+}
+
+#[test]
+fn save_and_load() {
+    let session = GameSession::new();
+    for &(row, col) in [(7, 7), (7, 8), (8, 8)].iter() {
+        session.put_chess(coord(row, col)).unwrap();
+    }
+    let steps = session.steps();
+
+    let directory = env::temp_dir();
+    for name in ["session_test.sgf", "session_test.psq", "session_test.rif", "session_test.txt"].iter() {
+        let path = directory.join(format!("{}_{}", std::process::id(), name));
+        let path = path.to_str().unwrap();
+        session.save_game(path).unwrap();
+
+        let loaded = GameSession::new();
+        loaded.load_game(path).unwrap();
+        assert_eq!(loaded.steps(), steps);
+        assert_eq!(loaded.next_chess(), CtWhite);
+        fs::remove_file(path).unwrap();
+    }
+
+    let loaded = GameSession::new();
+    assert_eq!(loaded.load_game(directory.join("no_such_game.sgf").to_str().unwrap())
+                   .unwrap_err().kind, ErrorKind::IoFailed);
+}
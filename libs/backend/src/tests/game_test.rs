@@ -0,0 +1,257 @@
+use super::super::board::*;
+use super::super::game::*;
+use super::super::utils::*;
+
+use super::super::cross_point::ChessType::*;
+use super::super::game::Lifecycle::*;
+use super::super::rule_checker::GameStatus::*;
+
+#[test]
+fn lifecycle() {
+    let game = Game::new();
+    assert_eq!(game.lifecycle(), LcNotStarted);
+    assert_eq!(game.put_chess(Coord{row: 7, col: 7}, CtBlack).unwrap_err().kind,
+               ErrorKind::GameNotStarted);
+    assert_eq!(game.take_back().unwrap_err().kind, ErrorKind::GameNotStarted);
+
+    game.start().unwrap();
+    assert_eq!(game.lifecycle(), LcInProgress);
+    assert_eq!(game.start().unwrap_err().kind, ErrorKind::GameAlreadyStarted);
+
+    for col in 3..7 {
+        game.put_chess(Coord{row: 7, col}, CtBlack).unwrap();
+        game.put_chess(Coord{row: 8, col}, CtWhite).unwrap();
+    }
+    game.put_chess(Coord{row: 7, col: 7}, CtBlack).unwrap();
+    assert_eq!(game.lifecycle(), LcFinished);
+    assert_eq!(game.game_status(), GsGameOver(CtBlack));
+    assert_eq!(game.put_chess(Coord{row: 8, col: 7}, CtWhite).unwrap_err().kind,
+               ErrorKind::GameAlreadyOver);
+    assert_eq!(game.board().have_chess_at(Coord{row: 8, col: 7}), false);
+
+    assert_eq!(game.take_back().unwrap().map(|step| step.coord), Some(Coord{row: 7, col: 7}));
+    assert_eq!(game.lifecycle(), LcInProgress);
+    assert_eq!(game.next_chess(), CtBlack);
+
+    game.reset().unwrap();
+    assert_eq!(game.lifecycle(), LcNotStarted);
+    assert_eq!(game.steps().len(), 0);
+    assert_eq!(game.next_chess(), CtBlack);
+    assert_eq!(game.board().have_chess_at(Coord{row: 7, col: 3}), false);
+}
+
+#[test]
+fn replay() {
+    let game = Game::new();
+    let coords = [Coord{row: 7, col: 7}, Coord{row: 8, col: 8}, Coord{row: 7, col: 8}];
+    game.replay(&coords).unwrap();
+
+    assert_eq!(game.lifecycle(), LcInProgress);
+    assert_eq!(game.steps().iter().map(|step| step.chess).collect::<Vec<_>>(),
+               vec![CtBlack, CtWhite, CtBlack]);
+    assert_eq!(game.next_chess(), CtWhite);
+
+    let coords = [Coord{row: 7, col: 7}, Coord{row: 7, col: 7}];
+    assert_eq!(game.replay(&coords).unwrap_err().kind, ErrorKind::CellOccupied);
+}
+
+#[test]
+fn turn_order() {
+    let game = Game::new();
+    game.start().unwrap();
+
+    assert_eq!(game.put_chess(Coord{row: 7, col: 7}, CtWhite).unwrap_err().kind,
+               ErrorKind::WrongTurn);
+    game.put_chess(Coord{row: 7, col: 7}, CtBlack).unwrap();
+    assert_eq!(game.put_chess(Coord{row: 7, col: 8}, CtBlack).unwrap_err().kind,
+               ErrorKind::WrongTurn);
+    assert_eq!(game.put_chess(Coord{row: 7, col: 7}, CtWhite).unwrap_err().kind,
+               ErrorKind::CellOccupied);
+    assert_eq!(game.next_chess(), CtWhite);
+    game.put_chess(Coord{row: 7, col: 8}, CtWhite).unwrap();
+}
+
+#[test]
+fn replay_steps() {
+    let game = Game::new();
+    let steps = [CoordAndChess{coord: Coord{row: 7, col: 7}, chess: CtBlack},
+                 CoordAndChess{coord: Coord{row: 8, col: 8}, chess: CtWhite}];
+    game.replay_steps(&steps).unwrap();
+    assert_eq!(game.steps(), steps.to_vec());
+    assert_eq!(game.next_chess(), CtBlack);
+
+    let steps = [CoordAndChess{coord: Coord{row: 7, col: 7}, chess: CtWhite}];
+    assert_eq!(game.replay_steps(&steps).unwrap_err().kind, ErrorKind::WrongTurn);
+}
+
+#[test]
+fn take_back_and_redo() {
+    let game = Game::new();
+    let coords = [Coord{row: 7, col: 7}, Coord{row: 8, col: 8}, Coord{row: 7, col: 8}];
+    game.replay(&coords).unwrap();
+
+    game.take_back().unwrap();
+    game.take_back().unwrap();
+    assert_eq!(game.steps().len(), 1);
+    assert_eq!(game.board().have_chess_at(Coord{row: 8, col: 8}), false);
+
+    assert_eq!(game.redo().unwrap().map(|step| step.coord), Some(Coord{row: 8, col: 8}));
+    assert_eq!(game.redo().unwrap().map(|step| step.coord), Some(Coord{row: 7, col: 8}));
+    assert_eq!(game.redo().unwrap(), None);
+    assert_eq!(game.board().have_chess_at(Coord{row: 7, col: 8}), true);
+    assert_eq!(game.next_chess(), CtWhite);
+}
+
+#[test]
+fn variations() {
+    let game = Game::new();
+    game.replay(&[Coord{row: 7, col: 7}, Coord{row: 8, col: 8}]).unwrap();
+    let main = game.current_node();
+
+    // trying another move keeps the first line as a variation
+    game.take_back().unwrap();
+    game.put_chess(Coord{row: 6, col: 6}, CtWhite).unwrap();
+    let other = game.current_node();
+    game.put_chess(Coord{row: 7, col: 8}, CtBlack).unwrap();
+    assert_eq!(game.steps().len(), 3);
+
+    game.take_back().unwrap();
+    game.take_back().unwrap();
+    assert_eq!(game.variations().iter().map(|variation| variation.0).collect::<Vec<_>>(),
+               vec![main, other]);
+
+    // redo goes on with the line taken back last
+    game.redo().unwrap();
+    assert_eq!(game.current_node(), other);
+    assert_eq!(game.redo().unwrap().map(|step| step.coord), Some(Coord{row: 7, col: 8}));
+
+    // playing the same move again goes into the existing node
+    game.go_to(main).unwrap();
+    assert_eq!(game.board().have_chess_at(Coord{row: 6, col: 6}), false);
+    assert_eq!(game.board().have_chess_at(Coord{row: 7, col: 8}), false);
+    assert_eq!(game.board().have_chess_at(Coord{row: 8, col: 8}), true);
+    game.take_back().unwrap();
+    game.put_chess(Coord{row: 8, col: 8}, CtWhite).unwrap();
+    assert_eq!(game.current_node(), main);
+    assert_eq!(game.variations().len(), 0);
+
+    assert_eq!(game.next_variation(), Ok(true));
+    assert_eq!(game.current_node(), other);
+    assert_eq!(game.next_variation(), Ok(true));
+    assert_eq!(game.current_node(), main);
+
+    assert_eq!(game.go_to(100).unwrap_err().kind, ErrorKind::NodeNotFound);
+}
+
+#[test]
+fn sgf() {
+    let game = Game::new();
+    game.replay(&[Coord{row: 7, col: 7}, Coord{row: 8, col: 8}]).unwrap();
+    game.take_back().unwrap();
+    game.put_chess(Coord{row: 6, col: 6}, CtWhite).unwrap();
+    game.set_comment(game.current_node(), Some("better".to_string())).unwrap();
+
+    let record = game.to_sgf();
+    assert_eq!(record.write(), "(;GM[4]FF[4]CA[UTF-8]SZ[15]RU[Freestyle];B[hh](;W[ii])\
+                                (;W[gg]C[better]))\n");
+
+    let game_r = Game::new();
+    game_r.load_sgf(&record).unwrap();
+    assert_eq!(game_r.lifecycle(), LcInProgress);
+    assert_eq!(game_r.steps().iter().map(|step| step.coord).collect::<Vec<_>>(),
+               vec![Coord{row: 7, col: 7}, Coord{row: 8, col: 8}]);
+    assert_eq!(game_r.to_sgf(), record);
+}
+
+#[test]
+fn jump_to() {
+    let game = Game::new();
+    let coords = [Coord{row: 7, col: 7}, Coord{row: 8, col: 8}, Coord{row: 7, col: 8},
+                  Coord{row: 6, col: 6}];
+    game.replay(&coords).unwrap();
+
+    game.jump_to(1).unwrap();
+    assert_eq!(game.steps().len(), 1);
+    assert_eq!(game.next_chess(), CtWhite);
+    game.jump_to(3).unwrap();
+    assert_eq!(game.last_step().map(|step| step.coord), Some(Coord{row: 7, col: 8}));
+    game.jump_to(0).unwrap();
+    assert_eq!(game.steps().len(), 0);
+    assert_eq!(game.board().have_chess_at(Coord{row: 7, col: 7}), false);
+
+    assert_eq!(game.jump_to(5).unwrap_err().kind, ErrorKind::NodeNotFound);
+    assert_eq!(game.steps().len(), 0);
+    game.jump_to(4).unwrap();
+    assert_eq!(game.steps().iter().map(|step| step.coord).collect::<Vec<_>>(), coords.to_vec());
+}
+
+#[test]
+fn setup() {
+    let game = Game::new();
+    game.replay(&[Coord{row: 7, col: 7}, Coord{row: 8, col: 8}]).unwrap();
+    assert_eq!(game.setup_put(Coord{row: 0, col: 0}, CtBlack).unwrap_err().kind,
+               ErrorKind::GameAlreadyStarted);
+
+    // the moves played become the set up position
+    game.begin_setup().unwrap();
+    assert_eq!(game.lifecycle(), LcSetup);
+    assert_eq!(game.steps().len(), 0);
+    assert_eq!(game.setup_stones().len(), 2);
+    assert_eq!(game.put_chess(Coord{row: 0, col: 0}, CtBlack).unwrap_err().kind,
+               ErrorKind::GameNotStarted);
+
+    game.setup_put(Coord{row: 0, col: 0}, CtWhite).unwrap();
+    game.setup_put(Coord{row: 0, col: 1}, CtWhite).unwrap();
+    assert_eq!(game.finish_setup(CtBlack).unwrap_err().kind, ErrorKind::PositionInvalid);
+    assert_eq!(game.setup_remove(Coord{row: 0, col: 1}), Ok(CtWhite));
+    assert_eq!(game.setup_put(Coord{row: 0, col: 0}, CtBlack).unwrap_err().kind,
+               ErrorKind::CellOccupied);
+    game.setup_put(Coord{row: 1, col: 1}, CtBlack).unwrap();
+
+    // black has one more, so white is to move
+    game.setup_put(Coord{row: 2, col: 2}, CtBlack).unwrap();
+    assert_eq!(game.finish_setup(CtBlack).unwrap_err().kind, ErrorKind::PositionInvalid);
+    assert_eq!(game.lifecycle(), LcSetup);
+    assert_eq!(game.setup_remove(Coord{row: 2, col: 2}), Ok(CtBlack));
+    assert_eq!(game.finish_setup(CtWhite).unwrap_err().kind, ErrorKind::PositionInvalid);
+
+    game.finish_setup(CtBlack).unwrap();
+    assert_eq!(game.lifecycle(), LcInProgress);
+    assert_eq!(game.next_chess(), CtBlack);
+    game.put_chess(Coord{row: 6, col: 6}, CtBlack).unwrap();
+    game.take_back().unwrap();
+    assert_eq!(game.take_back(), Ok(None));
+    assert_eq!(game.board().have_chess_at(Coord{row: 0, col: 0}), true);
+
+    game.reset().unwrap();
+    assert_eq!(game.setup_stones().len(), 0);
+    assert_eq!(game.board().have_chess_at(Coord{row: 0, col: 0}), false);
+}
+
+#[test]
+fn setup_already_over() {
+    let game = Game::new();
+    game.begin_setup().unwrap();
+    for col in 0..5 {
+        game.setup_put(Coord{row: 3, col}, CtBlack).unwrap();
+        game.setup_put(Coord{row: 5, col: col + 1}, CtWhite).unwrap();
+    }
+    game.setup_remove(Coord{row: 5, col: 3}).unwrap();
+    assert_eq!(game.finish_setup(CtWhite).unwrap_err().kind, ErrorKind::PositionInvalid);
+    assert_eq!(game.lifecycle(), LcSetup);
+
+    game.setup_remove(Coord{row: 3, col: 2}).unwrap();
+    game.setup_put(Coord{row: 3, col: 6}, CtBlack).unwrap();
+    game.finish_setup(CtWhite).unwrap();
+    assert_eq!(game.next_chess(), CtWhite);
+
+    // the set up position and the side to move go through sgf
+    game.put_chess(Coord{row: 5, col: 3}, CtWhite).unwrap();
+    assert_eq!(game.lifecycle(), LcFinished);
+    let record = game.to_sgf();
+    let game_r = Game::new();
+    game_r.load_sgf(&record).unwrap();
+    assert_eq!(game_r.setup_stones(), game.setup_stones());
+    assert_eq!(game_r.steps(), game.steps());
+    assert_eq!(game_r.game_status(), GsGameOver(CtWhite));
+}
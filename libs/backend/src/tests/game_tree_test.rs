@@ -0,0 +1,24 @@
+use super::super::board::*;
+use super::super::game_tree::*;
+use super::super::utils::*;
+
+use super::super::cross_point::ChessType::*;
+
+#[test]
+fn add_and_path() {
+    let mut tree = GameTree::new();
+    let black = CoordAndChess{coord: Coord{row: 7, col: 7}, chess: CtBlack};
+    let white = CoordAndChess{coord: Coord{row: 8, col: 8}, chess: CtWhite};
+
+    let first = tree.add_child(GAME_TREE_ROOT, black).unwrap();
+    let second = tree.add_child(first, white).unwrap();
+    assert_eq!(tree.add_child(GAME_TREE_ROOT, black), Ok(first));
+    assert_eq!(tree.path_to(second), Ok(vec![first, second]));
+    assert_eq!(tree.steps().len(), 0);
+
+    tree.set_current(second).unwrap();
+    assert_eq!(tree.steps(), vec![black, white]);
+    assert_eq!(tree.next_child(GAME_TREE_ROOT), Ok(Some(first)));
+    assert_eq!(tree.next_child(second), Ok(None));
+    assert_eq!(tree.path_to(10).unwrap_err().kind, ErrorKind::NodeNotFound);
+}
@@ -2,3 +2,15 @@ mod cross_point_test;
 mod board_test;
 mod tuple_test;
 mod rule_checker_test;
+mod evaluation_dfa_test;
+mod forbidden_checker_test;
+mod opening_test;
+mod game_test;
+mod game_tree_test;
+mod psq_test;
+mod notation_test;
+mod rif_test;
+mod sgf_test;
+mod position_test;
+mod zobrist_test;
+mod game_session_test;
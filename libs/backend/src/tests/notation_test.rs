@@ -0,0 +1,24 @@
+use super::super::board::*;
+use super::super::notation::*;
+use super::super::utils::*;
+
+#[test]
+fn write_and_parse() {
+    let coords = vec![Coord{row: 7, col: 7}, Coord{row: 6, col: 8}, Coord{row: 5, col: 9}];
+    assert_eq!(write_moves(&coords, 15), Ok("h8 i9 j10".to_string()));
+    assert_eq!(write_moves(&[], 15), Ok(String::new()));
+    assert_eq!(write_moves(&[Coord{row: 15, col: 0}], 15).unwrap_err().kind, ErrorKind::CoordInvalid);
+
+    assert_eq!(parse_moves("h8 i9 j10", 15), Ok(coords.clone()));
+    assert_eq!(parse_moves("h8i9j10", 15), Ok(coords.clone()));
+    assert_eq!(parse_moves(" H8,\ni9;  J10\n", 15), Ok(coords.clone()));
+    assert_eq!(parse_moves("", 15), Ok(Vec::new()));
+}
+
+#[test]
+fn parse_invalid() {
+    assert_eq!(parse_moves("h8 q9", 15).unwrap_err().kind, ErrorKind::CoordInvalid);
+    assert_eq!(parse_moves("h8 i16", 15).unwrap_err().kind, ErrorKind::CoordInvalid);
+    assert_eq!(parse_moves("8 h8", 15).unwrap_err().kind, ErrorKind::CoordInvalid);
+    assert_eq!(parse_moves("h8 - i9", 15).unwrap_err().kind, ErrorKind::RecordInvalid);
+}
@@ -0,0 +1,163 @@
+use super::super::board::*;
+use super::super::opening::*;
+use super::super::utils::*;
+
+use super::super::cross_point::ChessType::*;
+use super::super::opening::OpeningRule::*;
+use super::super::opening::OpeningPhase::*;
+use super::super::opening::OpeningAction::*;
+use super::super::opening::Player::*;
+
+#[test]
+fn free() {
+    let opening = Opening::create_with_rule(OrFree, 15);
+    assert_eq!(opening.is_finished(), true);
+    assert_eq!(opening.legal_actions(), vec![]);
+    assert_eq!(opening.get_player(CtBlack), PlFirst);
+    assert_eq!(opening.place(Coord{row: 7, col: 7}).unwrap_err().kind,
+               ErrorKind::OpeningActionInvalid);
+}
+
+#[test]
+fn swap() {
+    let opening = Opening::create_with_rule(OrSwap, 15);
+    assert_eq!(opening.phase(), OpPlaceChess(CtBlack));
+    assert_eq!(opening.current_player(), PlFirst);
+    assert_eq!(opening.legal_actions(), vec![OaPlace]);
+    assert_eq!(opening.choose_color(CtWhite).is_err(), true);
+
+    assert_eq!(opening.place(Coord{row: 7, col: 7}), Ok(CtBlack));
+    assert_eq!(opening.place(Coord{row: 7, col: 8}), Ok(CtWhite));
+    assert_eq!(opening.current_player(), PlFirst);
+    assert_eq!(opening.place(Coord{row: 8, col: 8}), Ok(CtBlack));
+
+    assert_eq!(opening.phase(), OpChooseColor);
+    assert_eq!(opening.current_player(), PlSecond);
+    assert_eq!(opening.place(Coord{row: 9, col: 9}).is_err(), true);
+    assert_eq!(opening.choose_color(CtBlack), Ok(()));
+
+    assert_eq!(opening.is_finished(), true);
+    assert_eq!(opening.get_player(CtBlack), PlSecond);
+    assert_eq!(opening.get_chess(PlFirst), CtWhite);
+    assert_eq!(opening.current_player(), PlFirst);
+}
+
+#[test]
+fn swap2() {
+    let opening = Opening::create_with_rule(OrSwap2, 15);
+    opening.place(Coord{row: 7, col: 7}).unwrap();
+    opening.place(Coord{row: 7, col: 8}).unwrap();
+    opening.place(Coord{row: 8, col: 8}).unwrap();
+    assert_eq!(opening.phase(), OpSwap2Choice);
+    assert_eq!(opening.legal_actions(), vec![OaChooseColor, OaPlaceTwo]);
+    assert_eq!(opening.current_player(), PlSecond);
+
+    assert_eq!(opening.place_two(), Ok(()));
+    assert_eq!(opening.place(Coord{row: 6, col: 6}), Ok(CtWhite));
+    assert_eq!(opening.current_player(), PlSecond);
+    assert_eq!(opening.place(Coord{row: 6, col: 7}), Ok(CtBlack));
+
+    assert_eq!(opening.phase(), OpChooseColor);
+    assert_eq!(opening.current_player(), PlFirst);
+    assert_eq!(opening.choose_color(CtWhite), Ok(()));
+    assert_eq!(opening.is_finished(), true);
+    assert_eq!(opening.get_player(CtWhite), PlFirst);
+    assert_eq!(opening.current_player(), PlFirst);
+
+    let opening = Opening::create_with_rule(OrSwap2, 15);
+    opening.place(Coord{row: 7, col: 7}).unwrap();
+    opening.place(Coord{row: 7, col: 8}).unwrap();
+    opening.place(Coord{row: 8, col: 8}).unwrap();
+    assert_eq!(opening.choose_color(CtWhite), Ok(()));
+    assert_eq!(opening.is_finished(), true);
+    assert_eq!(opening.get_player(CtWhite), PlSecond);
+}
+
+#[test]
+fn soosorv() {
+    let opening = Opening::create_with_rule(OrSoosorv, 15);
+    opening.place(Coord{row: 7, col: 7}).unwrap();
+    opening.place(Coord{row: 7, col: 8}).unwrap();
+    opening.place(Coord{row: 8, col: 8}).unwrap();
+    assert_eq!(opening.choose_color(CtWhite), Ok(()));
+
+    assert_eq!(opening.phase(), OpPlaceChess(CtWhite));
+    assert_eq!(opening.current_player(), PlSecond);
+    assert_eq!(opening.place(Coord{row: 6, col: 8}), Ok(CtWhite));
+
+    assert_eq!(opening.phase(), OpDeclareAlternatives);
+    assert_eq!(opening.declare_alternatives(0).is_err(), true);
+    assert_eq!(opening.declare_alternatives(9).is_err(), true);
+    assert_eq!(opening.declare_alternatives(2), Ok(()));
+    assert_eq!(opening.alternative_count(), 2);
+
+    assert_eq!(opening.phase(), OpChooseColor);
+    assert_eq!(opening.current_player(), PlFirst);
+    assert_eq!(opening.choose_color(CtBlack), Ok(()));
+
+    assert_eq!(opening.phase(), OpPlaceAlternative);
+    assert_eq!(opening.current_player(), PlFirst);
+    assert_eq!(opening.place(Coord{row: 9, col: 9}), Ok(CtBlack));
+    assert_eq!(opening.place(Coord{row: 9, col: 9}).unwrap_err().kind, ErrorKind::CellOccupied);
+    assert_eq!(opening.place(Coord{row: 5, col: 5}), Ok(CtBlack));
+    assert_eq!(opening.alternatives(), vec![Coord{row: 9, col: 9}, Coord{row: 5, col: 5}]);
+
+    assert_eq!(opening.phase(), OpSelectAlternative);
+    assert_eq!(opening.current_player(), PlSecond);
+    assert_eq!(opening.select_alternative(Coord{row: 7, col: 7}).is_err(), true);
+    assert_eq!(opening.chess_count(), 4);
+    assert_eq!(opening.select_alternative(Coord{row: 5, col: 5}),
+               Ok(vec![Coord{row: 9, col: 9}]));
+    assert_eq!(opening.is_finished(), true);
+    assert_eq!(opening.chess_count(), 5);
+    assert_eq!(opening.get_player(CtWhite), PlSecond);
+}
+
+#[test]
+fn taraguchi() {
+    let opening = Opening::create_with_rule(OrTaraguchi, 15);
+    assert_eq!(opening.place(Coord{row: 7, col: 8}).unwrap_err().kind, ErrorKind::CoordInvalid);
+    assert_eq!(opening.place(Coord{row: 7, col: 7}), Ok(CtBlack));
+    assert_eq!(opening.current_player(), PlSecond);
+    assert_eq!(opening.choose_color(CtWhite), Ok(()));
+
+    assert_eq!(opening.phase(), OpPlaceChess(CtWhite));
+    assert_eq!(opening.current_player(), PlSecond);
+    assert_eq!(opening.place(Coord{row: 5, col: 7}).is_err(), true);
+    assert_eq!(opening.place(Coord{row: 6, col: 8}), Ok(CtWhite));
+    assert_eq!(opening.current_player(), PlFirst);
+    assert_eq!(opening.choose_color(CtWhite), Ok(()));
+
+    assert_eq!(opening.phase(), OpPlaceChess(CtBlack));
+    assert_eq!(opening.current_player(), PlSecond);
+    assert_eq!(opening.place(Coord{row: 9, col: 9}), Ok(CtBlack));
+    assert_eq!(opening.choose_color(CtBlack), Ok(()));
+
+    assert_eq!(opening.current_player(), PlSecond);
+    assert_eq!(opening.place(Coord{row: 11, col: 7}).is_err(), true);
+    assert_eq!(opening.place(Coord{row: 10, col: 7}), Ok(CtWhite));
+    assert_eq!(opening.choose_color(CtBlack), Ok(()));
+
+    assert_eq!(opening.phase(), OpTaraguchiFifth);
+    assert_eq!(opening.legal_actions(), vec![OaPlace, OaOfferAlternatives]);
+    assert_eq!(opening.current_player(), PlFirst);
+    assert_eq!(opening.offer_alternatives(), Ok(()));
+    for col in 0..10 {
+        assert_eq!(opening.place(Coord{row: 0, col}), Ok(CtBlack));
+    }
+    assert_eq!(opening.phase(), OpSelectAlternative);
+    assert_eq!(opening.current_player(), PlSecond);
+    assert_eq!(opening.select_alternative(Coord{row: 0, col: 3}).unwrap().len(), 9);
+    assert_eq!(opening.is_finished(), true);
+
+    let opening = Opening::create_with_rule(OrTaraguchi, 15);
+    for &(row, col) in [(7, 7), (6, 7), (8, 8), (4, 4)].iter() {
+        opening.place(Coord{row, col}).unwrap();
+        opening.choose_color(CtBlack).unwrap();
+    }
+    assert_eq!(opening.place(Coord{row: 2, col: 2}).is_err(), true);
+    assert_eq!(opening.place(Coord{row: 3, col: 3}), Ok(CtBlack));
+    assert_eq!(opening.phase(), OpChooseColor);
+    assert_eq!(opening.choose_color(CtWhite), Ok(()));
+    assert_eq!(opening.is_finished(), true);
+}
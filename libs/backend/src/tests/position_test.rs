@@ -0,0 +1,96 @@
+use super::super::board::*;
+use super::super::cross_point::*;
+use super::super::game::*;
+use super::super::position::*;
+use super::super::utils::*;
+
+use super::super::cross_point::ChessType::*;
+use super::super::game::Lifecycle::*;
+
+fn stone(row: usize, col: usize, chess: ChessType) -> CoordAndChess {
+    return CoordAndChess{coord: Coord{row, col}, chess};
+}
+
+#[test]
+fn display() {
+    let position = Position::create_with_stones(3, &[stone(2, 0, CtWhite), stone(1, 1, CtBlack)],
+                                                CtBlack).unwrap();
+    assert_eq!(position.to_string(), "3 3/1x1/o2 b");
+    assert_eq!(position.stones(), &[stone(1, 1, CtBlack), stone(2, 0, CtWhite)]);
+    assert_eq!(position.chess_at(Coord{row: 2, col: 0}), Some(CtWhite));
+    assert_eq!(position.chess_at(Coord{row: 0, col: 0}), None);
+
+    let board = Board::new();
+    board.put_chess_at(Coord{row: 7, col: 7}, CtBlack).unwrap();
+    board.put_chess_at(Coord{row: 7, col: 14}, CtWhite).unwrap();
+    assert_eq!(Position::create_with_board(&board, CtWhite).to_string(),
+               "15 15/15/15/15/15/15/15/7x6o/15/15/15/15/15/15/15 w");
+}
+
+#[test]
+fn from_str() {
+    let position: Position = "3 3/1x1/o2 b".parse().unwrap();
+    assert_eq!(position.board_size(), 3);
+    assert_eq!(position.next_chess(), CtBlack);
+    assert_eq!(position.stones(), &[stone(1, 1, CtBlack), stone(2, 0, CtWhite)]);
+
+    let board = position.to_board();
+    assert_eq!(board.size(), 3);
+    assert_eq!(board.get_chess_at(Coord{row: 1, col: 1}), Ok(CtBlack));
+    assert_eq!(Position::create_with_board(&board, CtBlack), position);
+
+    let position: Position = "12 12/12/12/12/12/12/12/12/12/12/12/xoxoxoxoxoxo w".parse().unwrap();
+    assert_eq!(position.stones().len(), 12);
+    assert_eq!(position.to_string().parse::<Position>(), Ok(position.clone()));
+
+    let texts = ["", "3 3/1x1/o2", "3 3/1x1 b", "3 3/1x1/o3 b", "3 3/1x1/o1 b", "3 3/1y1/o2 b",
+                 "3 3/1x1/o2 x", "0  b", "3 3/0x2/o2 b", "1 18446744073709551615x b",
+                 "1 99999999999999999999 b", "2 xxx/2 b"];
+    for text in texts.iter() {
+        assert_eq!(text.parse::<Position>().unwrap_err().kind, ErrorKind::PositionInvalid);
+    }
+}
+
+#[test]
+fn pack() {
+    let position: Position = "3 3/1x1/o2 w".parse().unwrap();
+    let packed = position.pack();
+    assert_eq!(packed.bytes(), &[3, 1, 0x10, 0x00, 0x40, 0x00]);
+    assert_eq!(packed.to_string(), "030110004000");
+    assert_eq!(packed.unpack(), Ok(position));
+
+    let position: Position = "15 15/15/15/15/15/15/15/7x6o/15/15/15/15/15/15/o14 b".parse().unwrap();
+    let packed: PackedPosition = position.pack().to_string().parse().unwrap();
+    assert_eq!(packed.bytes().len(), 2 + 29 * 2);
+    assert_eq!(packed.unpack(), Ok(position));
+
+    let texts = ["", "0301100040", "030110004000ff", "0302100040000", "03021000400g", "030210004000"];
+    for text in texts.iter() {
+        let error = text.parse::<PackedPosition>().and_then(|packed| packed.unpack()).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::PositionInvalid);
+    }
+
+    // the bit of the tenth cross point is beyond a 3x3 board
+    let packed = PackedPosition::create_with_bytes(vec![3, 0, 0x00, 0x02, 0x00, 0x00]);
+    assert_eq!(packed.unpack().unwrap_err().kind, ErrorKind::PositionInvalid);
+    // black and white on one cross point
+    let packed = PackedPosition::create_with_bytes(vec![3, 0, 0x01, 0x00, 0x01, 0x00]);
+    assert_eq!(packed.unpack().unwrap_err().kind, ErrorKind::PositionInvalid);
+}
+
+#[test]
+fn game_position() {
+    let game = Game::new();
+    game.replay(&[Coord{row: 7, col: 7}, Coord{row: 7, col: 14}]).unwrap();
+    let position = game.position();
+    assert_eq!(position.to_string(), "15 15/15/15/15/15/15/15/7x6o/15/15/15/15/15/15/15 b");
+
+    let game_r = Game::new();
+    game_r.load_position(&position).unwrap();
+    assert_eq!(game_r.lifecycle(), LcInProgress);
+    assert_eq!(game_r.setup_stones().len(), 2);
+    assert_eq!(game_r.position(), position);
+
+    let position: Position = "3 3/3/3 b".parse().unwrap();
+    assert_eq!(game_r.load_position(&position).unwrap_err().kind, ErrorKind::PositionInvalid);
+}
@@ -0,0 +1,44 @@
+use super::super::board::*;
+use super::super::psq::*;
+use super::super::utils::*;
+
+#[test]
+fn write() {
+    let coords = [Coord{row: 7, col: 7}, Coord{row: 7, col: 8}, Coord{row: 0, col: 14}];
+    let record = PsqRecord::create_with_coords(15, &coords);
+
+    assert_eq!(record.write(), "Piskvorky 15x15, 11:11, 0\n8,8,0\n9,8,0\n15,1,0\n-1\n");
+    assert_eq!(PsqRecord::parse(&record.write()), Ok(record.clone()));
+    assert_eq!(record.coords(), coords.to_vec());
+}
+
+#[test]
+fn parse() {
+    let text = "Piskvorky 20x20, 11:11, 0\r\n10,10,1250\r\n11,10,300\r\n\r\n-1\r\n\
+                pbrain-embryo.exe\r\n";
+    let record = PsqRecord::parse(text).unwrap();
+
+    assert_eq!(record.board_size, 20);
+    assert_eq!(record.moves, vec![PsqMove { coord: Coord{row: 9, col: 9}, time: 1250 },
+                                  PsqMove { coord: Coord{row: 9, col: 10}, time: 300 }]);
+
+    let record = PsqRecord::parse("Piskvorky 15x15, 11:11, 0\n").unwrap();
+    assert_eq!(record.moves.len(), 0);
+}
+
+#[test]
+fn parse_invalid() {
+    let texts = [
+        "",
+        "Gomoku 15x15, 11:11, 0\n8,8,0\n",
+        "Piskvorky 15x20, 11:11, 0\n8,8,0\n",
+        "Piskvorky 0x0, 11:11, 0\n",
+        "Piskvorky 15x15, 11:11, 0\n16,8,0\n",
+        "Piskvorky 15x15, 11:11, 0\n0,8,0\n",
+        "Piskvorky 15x15, 11:11, 0\n8,8,-5\n",
+    ];
+
+    for text in texts.iter() {
+        assert_eq!(PsqRecord::parse(text).unwrap_err().kind, ErrorKind::RecordInvalid);
+    }
+}
@@ -0,0 +1,86 @@
+use super::super::board::*;
+use super::super::rif::*;
+use super::super::utils::*;
+
+use super::super::rif::RifRecordType::*;
+
+const RIF_TEXT: &'static str = r#"<?xml version="1.0" encoding="utf-8"?>
+<!-- exported from renjunet -->
+<database>
+<players>
+<player id="1" name="Anna &amp; Co" surname="Karlsson" country="3"/>
+</players>
+<games>
+<game id="7" tournament="2" round="1" black="1" white="2" bresult="1" rule="1">
+<move>h8 i9 j10</move>
+</game>
+<game id="8" bresult='0.5'/>
+</games>
+<problems>
+<problem id="3" name="VCF &lt;easy&gt;">
+<move>h8h9i8</move>
+</problem>
+</problems>
+</database>
+"#;
+
+#[test]
+fn parse() {
+    let database = RifDatabase::parse(RIF_TEXT).unwrap();
+    assert_eq!(database.records.len(), 3);
+
+    let games = database.records_of_type(RtGame);
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].attribute("id"), Some("7"));
+    assert_eq!(games[0].attribute("bresult"), Some("1"));
+    assert_eq!(games[0].attribute("name"), None);
+    assert_eq!(games[0].moves, vec![Coord{row: 7, col: 7}, Coord{row: 6, col: 8},
+                                    Coord{row: 5, col: 9}]);
+    assert_eq!(games[1].attribute("bresult"), Some("0.5"));
+    assert_eq!(games[1].moves.len(), 0);
+
+    let problems = database.records_of_type(RtProblem);
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].attribute("name"), Some("VCF <easy>"));
+    assert_eq!(problems[0].moves, vec![Coord{row: 7, col: 7}, Coord{row: 6, col: 7},
+                                       Coord{row: 7, col: 8}]);
+}
+
+#[test]
+fn write() {
+    let mut database = RifDatabase::parse(RIF_TEXT).unwrap();
+    let mut record = RifRecord::create_with_moves(RtGame, vec![Coord{row: 7, col: 7}]);
+    record.set_attribute("id", "9");
+    record.set_attribute("white", "\"Bo\"");
+    record.set_attribute("id", "10");
+    assert_eq!(record.attributes.len(), 2);
+    database.records.push(record);
+
+    let text = database.write().unwrap();
+    assert!(text.contains("<game id=\"10\" white=\"&quot;Bo&quot;\">\n<move>h8</move>\n</game>"));
+
+    let database_r = RifDatabase::parse(&text).unwrap();
+    assert_eq!(database_r.records_of_type(RtGame), database.records_of_type(RtGame));
+    assert_eq!(database_r.records_of_type(RtProblem), database.records_of_type(RtProblem));
+    assert_eq!(RifDatabase::new().write().unwrap(),
+               "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<database>\n</database>\n");
+
+    let mut database = RifDatabase::new();
+    database.records.push(RifRecord::create_with_moves(RtGame, vec![Coord{row: 15, col: 7}]));
+    assert_eq!(database.write().unwrap_err().kind, ErrorKind::CoordInvalid);
+}
+
+#[test]
+fn parse_invalid() {
+    let texts = [
+        "<games><game id=\"1\"><move>h8</move></games>",
+        "<games><game id=\"1\"><move>h8 z9</move></game></games>",
+        "<games><game id=1></game></games>",
+        "<games><game id=\"1\"",
+    ];
+
+    for text in texts.iter() {
+        assert!(RifDatabase::parse(text).is_err());
+    }
+    assert_eq!(RifDatabase::parse(texts[2]).unwrap_err().kind, ErrorKind::RecordInvalid);
+}
@@ -1,8 +1,11 @@
 use super::super::board::*;
 use super::super::rule_checker::*;
+use super::super::evaluation_dfa::*;
+use super::super::utils::*;
 
 use super::super::cross_point::ChessType::*;
 use super::super::rule_checker::GameStatus::*;
+use super::super::rule_checker::GameRule::*;
 
 #[test]
 fn check_game_status() {
@@ -11,61 +14,260 @@ fn check_game_status() {
             let board = Board::new();
             let rule_checker = RuleChecker::create_with_detail(board.clone());
 
-            board.put_chess_at(Coord{row: row + 0, col: col + 0}, CtBlack);     // X O _ X O
-            board.put_chess_at(Coord{row: row + 0, col: col + 1}, CtWhite);     // O _ X O _
-            board.put_chess_at(Coord{row: row + 0, col: col + 3}, CtBlack);     // _ X O _ X
-            board.put_chess_at(Coord{row: row + 0, col: col + 4}, CtWhite);     // X O _ X O
-            board.put_chess_at(Coord{row: row + 1, col: col + 0}, CtWhite);     // _ _ X O _
-            board.put_chess_at(Coord{row: row + 1, col: col + 2}, CtBlack);
-            board.put_chess_at(Coord{row: row + 1, col: col + 3}, CtWhite);
-            board.put_chess_at(Coord{row: row + 2, col: col + 1}, CtBlack);
-            board.put_chess_at(Coord{row: row + 2, col: col + 2}, CtWhite);
-            board.put_chess_at(Coord{row: row + 2, col: col + 4}, CtBlack);
-            board.put_chess_at(Coord{row: row + 3, col: col + 0}, CtBlack);
-            board.put_chess_at(Coord{row: row + 3, col: col + 1}, CtWhite);
-            board.put_chess_at(Coord{row: row + 3, col: col + 3}, CtBlack);
-            board.put_chess_at(Coord{row: row + 3, col: col + 4}, CtWhite);
-            board.put_chess_at(Coord{row: row + 4, col: col + 2}, CtBlack);
-            board.put_chess_at(Coord{row: row + 4, col: col + 3}, CtWhite);     // X O _ X O
+            board.put_chess_at(Coord{row: row + 0, col: col + 0}, CtBlack).unwrap();     // X O _ X O
+            board.put_chess_at(Coord{row: row + 0, col: col + 1}, CtWhite).unwrap();     // O _ X O _
+            board.put_chess_at(Coord{row: row + 0, col: col + 3}, CtBlack).unwrap();     // _ X O _ X
+            board.put_chess_at(Coord{row: row + 0, col: col + 4}, CtWhite).unwrap();     // X O _ X O
+            board.put_chess_at(Coord{row: row + 1, col: col + 0}, CtWhite).unwrap();     // _ _ X O _
+            board.put_chess_at(Coord{row: row + 1, col: col + 2}, CtBlack).unwrap();
+            board.put_chess_at(Coord{row: row + 1, col: col + 3}, CtWhite).unwrap();
+            board.put_chess_at(Coord{row: row + 2, col: col + 1}, CtBlack).unwrap();
+            board.put_chess_at(Coord{row: row + 2, col: col + 2}, CtWhite).unwrap();
+            board.put_chess_at(Coord{row: row + 2, col: col + 4}, CtBlack).unwrap();
+            board.put_chess_at(Coord{row: row + 3, col: col + 0}, CtBlack).unwrap();
+            board.put_chess_at(Coord{row: row + 3, col: col + 1}, CtWhite).unwrap();
+            board.put_chess_at(Coord{row: row + 3, col: col + 3}, CtBlack).unwrap();
+            board.put_chess_at(Coord{row: row + 3, col: col + 4}, CtWhite).unwrap();
+            board.put_chess_at(Coord{row: row + 4, col: col + 2}, CtBlack).unwrap();
+            board.put_chess_at(Coord{row: row + 4, col: col + 3}, CtWhite).unwrap();     // X O _ X O
             assert_eq!(rule_checker.check_game_status(), GsGameContinue);       // O _ X O _
                                                                                 // _ X O _ X
-            board.put_chess_at(Coord{row: row + 4, col: col + 0}, CtWhite);     // X O _ X O
+            board.put_chess_at(Coord{row: row + 4, col: col + 0}, CtWhite).unwrap();     // X O _ X O
             assert_eq!(rule_checker.check_game_status(), GsGameOver(CtWhite));  // O _ X O _
             assert_eq!(rule_checker.game_status(), GsGameOver(CtWhite));
 
-            board.remove_chess_at(Coord{row: row + 2, col: col + 2});           // X O _ X O
-            board.put_chess_at(Coord{row: row + 1, col: col + 1}, CtBlack);     // O X X O _
-            board.put_chess_at(Coord{row: row + 2, col: col + 2}, CtBlack);     // _ X X _ X
-            board.put_chess_at(Coord{row: row + 4, col: col + 4}, CtBlack);     // X O _ X O
+            board.remove_chess_at(Coord{row: row + 2, col: col + 2}).unwrap();           // X O _ X O
+            board.put_chess_at(Coord{row: row + 1, col: col + 1}, CtBlack).unwrap();     // O X X O _
+            board.put_chess_at(Coord{row: row + 2, col: col + 2}, CtBlack).unwrap();     // _ X X _ X
+            board.put_chess_at(Coord{row: row + 4, col: col + 4}, CtBlack).unwrap();     // X O _ X O
             assert_eq!(rule_checker.check_game_status(), GsGameOver(CtBlack));  // O _ X O X
             assert_eq!(rule_checker.game_status(), GsGameOver(CtBlack));
 
-            board.remove_chess_at(Coord{row: row + 0, col: col + 0});           // _ O X X O
-            board.put_chess_at(Coord{row: row + 0, col: col + 2}, CtBlack);     // O X X O _
-            board.put_chess_at(Coord{row: row + 3, col: col + 2}, CtBlack);     // _ X X _ X
+            board.remove_chess_at(Coord{row: row + 0, col: col + 0}).unwrap();           // _ O X X O
+            board.put_chess_at(Coord{row: row + 0, col: col + 2}, CtBlack).unwrap();     // O X X O _
+            board.put_chess_at(Coord{row: row + 3, col: col + 2}, CtBlack).unwrap();     // _ X X _ X
             assert_eq!(rule_checker.check_game_status(), GsGameOver(CtBlack));  // X O X X O
             assert_eq!(rule_checker.game_status(), GsGameOver(CtBlack));        // O _ X O X
 
-            board.remove_chess_at(Coord{row: row + 1, col: col + 1});
-            board.remove_chess_at(Coord{row: row + 1, col: col + 2});           // _ O X X O
-            board.put_chess_at(Coord{row: row + 1, col: col + 1}, CtWhite);     // O O O O O
-            board.put_chess_at(Coord{row: row + 1, col: col + 2}, CtWhite);     // _ X X _ X
-            board.put_chess_at(Coord{row: row + 1, col: col + 4}, CtWhite);     // X O X X O
+            board.remove_chess_at(Coord{row: row + 1, col: col + 1}).unwrap();
+            board.remove_chess_at(Coord{row: row + 1, col: col + 2}).unwrap();           // _ O X X O
+            board.put_chess_at(Coord{row: row + 1, col: col + 1}, CtWhite).unwrap();     // O O O O O
+            board.put_chess_at(Coord{row: row + 1, col: col + 2}, CtWhite).unwrap();     // _ X X _ X
+            board.put_chess_at(Coord{row: row + 1, col: col + 4}, CtWhite).unwrap();     // X O X X O
             assert_eq!(rule_checker.check_game_status(), GsGameOver(CtWhite));  // O _ X O X
             assert_eq!(rule_checker.game_status(), GsGameOver(CtWhite));
         }
     }
 }
 
+#[test]
+fn rule_variants() {
+    let rules = [GrFreestyle, GrStandard, GrRenju, GrCaro];
+    // whether black and white win with a five, an overline,
+    // a five blocked at both ends and a five at the edge
+    let expected = [
+        [true, true, true, true, true, true, true, true],
+        [true, true, false, false, true, true, true, true],
+        [true, true, false, true, true, true, true, true],
+        [true, true, true, true, false, false, true, true],
+    ];
+
+    for i in 0..rules.len() {
+        for j in 0..2 {
+            let chess = [CtBlack, CtWhite][j];
+            let other = chess.get_different_chess();
+
+            let board = Board::new();
+            let rule_checker = RuleChecker::create_with_rule(board.clone(), rules[i]);
+            for col in 3..8 {                                           // _ X X X X X _
+                board.put_chess_at(Coord{row: 7, col}, chess).unwrap();
+            }
+            assert_eq!(rule_checker.check_game_status() != GsGameContinue, expected[i][j]);
+
+            board.put_chess_at(Coord{row: 7, col: 8}, chess).unwrap();           // _ X X X X X X
+            assert_eq!(rule_checker.check_game_status() != GsGameContinue, expected[i][2 + j]);
+
+            board.remove_chess_at(Coord{row: 7, col: 8}).unwrap();               // O X X X X X O
+            board.put_chess_at(Coord{row: 7, col: 8}, other).unwrap();
+            board.put_chess_at(Coord{row: 7, col: 2}, other).unwrap();
+            assert_eq!(rule_checker.check_game_status() != GsGameContinue, expected[i][4 + j]);
+
+            let board = Board::new();
+            let rule_checker = RuleChecker::create_with_rule(board.clone(), rules[i]);
+            for row in 0..5 {                                           // | X X X X X O
+                board.put_chess_at(Coord{row, col: 0}, chess).unwrap();
+            }
+            board.put_chess_at(Coord{row: 5, col: 0}, other).unwrap();
+            assert_eq!(rule_checker.check_game_status() != GsGameContinue, expected[i][6 + j]);
+            if expected[i][6 + j] {
+                assert_eq!(rule_checker.game_status(), GsGameOver(chess));
+            }
+        }
+    }
+}
+
+#[test]
+fn winning_line() {
+    let board = Board::new();
+    let rule_checker = RuleChecker::create_with_rule(board.clone(), GrStandard);
+    for i in 2..7 {                                                     // diagonal five
+        board.put_chess_at(Coord{row: i, col: 8 - i}, CtWhite).unwrap();
+    }
+    assert_eq!(rule_checker.game_status(), GsGameOver(CtWhite));
+    assert_eq!(rule_checker.winning_line(),
+               Some((2..7).map(|i| Coord{row: i, col: 8 - i}).collect()));
+
+    board.remove_chess_at(Coord{row: 4, col: 4}).unwrap();
+    assert_eq!(rule_checker.game_status(), GsGameContinue);
+    assert_eq!(rule_checker.winning_line(), None);
+
+    for col in 3..9 {                                                   // X X X X X X
+        board.put_chess_at(Coord{row: 10, col}, CtBlack).unwrap();
+    }
+    assert_eq!(rule_checker.game_status(), GsGameContinue);
+    board.remove_chess_at(Coord{row: 10, col: 3}).unwrap();                      // _ X X X X X
+    assert_eq!(rule_checker.game_status(), GsGameOver(CtBlack));
+    assert_eq!(rule_checker.winning_line(),
+               Some((4..9).map(|col| Coord{row: 10, col}).collect()));
+
+    board.put_chess_at(Coord{row: 4, col: 4}, CtWhite).unwrap();                 // two fives at once
+    board.remove_chess_at(Coord{row: 10, col: 6}).unwrap();
+    assert_eq!(rule_checker.game_status(), GsGameOver(CtWhite));
+
+    let rule_checker_late = RuleChecker::create_with_rule(board.clone(), GrStandard);
+    assert_eq!(rule_checker_late.game_status(), GsGameOver(CtWhite));
+    assert_eq!(rule_checker_late.winning_line(), rule_checker.winning_line());
+}
+
+#[test]
+fn put_chess_at() {
+    let board = Board::new();
+    let rule_checker = RuleChecker::create_with_detail(board.clone());
+
+    assert_eq!(rule_checker.put_chess_at(Coord{row: 7, col: 7}, CtBlack), Ok(()));
+    assert_eq!(rule_checker.put_chess_at(Coord{row: 7, col: 7}, CtWhite).unwrap_err().kind,
+               ErrorKind::CellOccupied);
+    assert_eq!(rule_checker.put_chess_at(Coord{row: 15, col: 7}, CtWhite).unwrap_err().kind,
+               ErrorKind::CoordInvalid);
+
+    for col in 8..12 {
+        rule_checker.put_chess_at(Coord{row: 7, col}, CtBlack).unwrap();
+    }
+    assert_eq!(rule_checker.put_chess_at(Coord{row: 0, col: 0}, CtWhite).unwrap_err().kind,
+               ErrorKind::GameAlreadyOver);
+    assert_eq!(board.have_chess_at(Coord{row: 0, col: 0}), false);
+}
+
+#[test]
+fn draw() {
+    let board = Board::new();
+    let rule_checker = RuleChecker::create_with_detail(board.clone());
+    assert_eq!(rule_checker.open_tuple_count(), (15 * 11) * 2 + (11 * 11) * 2);
+
+    // X X O O repeated along every row and shifted by two on the next one,
+    // no line ever holds five chess of one color
+    let mut coords = Vec::new();
+    for row in 0..15 {
+        for col in 0..15 {
+            coords.push(Coord{row, col});
+        }
+    }
+    let (last, rest) = coords.split_last().unwrap();
+    for coord in rest.iter() {
+        let chess = match (coord.col + coord.row * 2) / 2 % 2 {
+            0 => CtBlack,
+            _ => CtWhite,
+        };
+        board.put_chess_at(*coord, chess).unwrap();
+        assert_eq!(rule_checker.winning_line(), None);
+    }
+    assert_eq!(rule_checker.open_tuple_count(), 0);
+    assert_eq!(rule_checker.is_early_draw(), false);
+    assert_eq!(rule_checker.game_status(), GsGameContinue);
+    rule_checker.set_early_draw(true);
+    assert_eq!(rule_checker.game_status(), GsDraw);
+    assert_eq!(rule_checker.get_simple_play(), Some(*last));
+
+    // a full board is a draw either way
+    rule_checker.set_early_draw(false);
+    board.put_chess_at(*last, CtBlack).unwrap();
+    assert_eq!(rule_checker.game_status(), GsDraw);
+    assert_eq!(rule_checker.get_simple_play(), None);
+
+    rule_checker.set_early_draw(true);
+    board.remove_chess_at(*last).unwrap();
+    board.remove_chess_at(Coord{row: 7, col: 7}).unwrap();
+    let rule_checker_late = RuleChecker::create_with_detail(board.clone());
+    rule_checker_late.set_early_draw(true);
+    assert_eq!(rule_checker_late.open_tuple_count(), rule_checker.open_tuple_count());
+    assert_eq!(rule_checker_late.game_status(), rule_checker.game_status());
+
+    for coord in rest.iter() {
+        if board.have_chess_at(*coord) {
+            board.remove_chess_at(*coord).unwrap();
+        }
+    }
+    assert_eq!(rule_checker.open_tuple_count(), (15 * 11) * 2 + (11 * 11) * 2);
+    assert_eq!(rule_checker.game_status(), GsGameContinue);
+}
+
 #[test]
 fn update_evaluation_by_event() {
     let board = Board::create_with_size(15);
     let rule_checker = RuleChecker::create_with_detail(board.clone());
+    let weights = EvaluationWeights::new();
 
-    board.put_chess_at(Coord{row: 13, col: 7}, CtWhite);
-    board.put_chess_at(Coord{row: 13, col: 9}, CtWhite);
-    board.put_chess_at(Coord{row: 13, col: 8}, CtBlack);
-    //board.remove_chess_at(Coord{row: 13, col: 8});
+    board.put_chess_at(Coord{row: 13, col: 7}, CtWhite).unwrap();
+    assert_eq!(rule_checker.get_evaluation(), 0);
+    board.put_chess_at(Coord{row: 13, col: 9}, CtWhite).unwrap();                // _ O _ O _
+    assert_eq!(rule_checker.get_evaluation(), -weights.open_two);
+    board.put_chess_at(Coord{row: 13, col: 8}, CtBlack).unwrap();                // _ O X O _
+    assert_eq!(rule_checker.get_evaluation(), 0);
+    board.remove_chess_at(Coord{row: 13, col: 8}).unwrap();
+    assert_eq!(rule_checker.get_evaluation(), -weights.open_two);
 
-    assert!(false);
-}
\ No newline at end of file
+    board.put_chess_at(Coord{row: 7, col: 7}, CtBlack).unwrap();                 // _ X X X _
+    board.put_chess_at(Coord{row: 7, col: 8}, CtBlack).unwrap();
+    board.put_chess_at(Coord{row: 7, col: 9}, CtBlack).unwrap();
+    assert_eq!(rule_checker.get_evaluation(),
+               weights.open_three - weights.open_two);
+    board.put_chess_at(Coord{row: 7, col: 10}, CtWhite).unwrap();                // _ X X X O
+    assert_eq!(rule_checker.get_evaluation(),
+               weights.closed_three - weights.open_two);
+    board.put_chess_at(Coord{row: 7, col: 6}, CtBlack).unwrap();                 // X X X X O
+    assert_eq!(rule_checker.get_evaluation(),
+               weights.closed_four - weights.open_two);
+    board.put_chess_at(Coord{row: 7, col: 5}, CtBlack).unwrap();                 // X X X X X O
+    assert_eq!(rule_checker.get_evaluation(), weights.five - weights.open_two);
+    assert_eq!(rule_checker.get_evaluation(), rule_checker.evaluate_board());
+
+    board.remove_chess_at(Coord{row: 7, col: 5}).unwrap();
+    board.remove_chess_at(Coord{row: 7, col: 10}).unwrap();
+    assert_eq!(rule_checker.get_evaluation(),
+               weights.open_four - weights.open_two);
+}
+
+#[test]
+fn evaluate_board() {
+    let board = Board::new();
+    let rule_checker = RuleChecker::create_with_detail(board.clone());
+    let mut seed: u32 = 17;
+
+    for _i in 0..300 {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        let coord = Coord{row: (seed >> 8) as usize % 15, col: (seed >> 16) as usize % 15};
+        let chess = match (seed >> 4) % 2 {
+            0 => CtBlack,
+            _ => CtWhite,
+        };
+
+        match board.have_chess_at(coord) {
+            true => { board.remove_chess_at(coord).unwrap(); },
+            false => board.put_chess_at(coord, chess).unwrap(),
+        }
+        assert_eq!(rule_checker.get_evaluation(), rule_checker.evaluate_board());
+    }
+
+    let rule_checker_late = RuleChecker::create_with_detail(board.clone());
+    assert_eq!(rule_checker_late.get_evaluation(), rule_checker.get_evaluation());
+}
@@ -0,0 +1,100 @@
+use super::super::board::*;
+use super::super::sgf::*;
+use super::super::utils::*;
+
+use super::super::cross_point::*;
+use super::super::cross_point::ChessType::*;
+use super::super::rule_checker::GameRule::*;
+
+fn step(row: usize, col: usize, chess: ChessType) -> CoordAndChess {
+    return CoordAndChess{coord: Coord{row, col}, chess};
+}
+
+#[test]
+fn write() {
+    let steps = [step(7, 7, CtBlack), step(6, 8, CtWhite), step(14, 0, CtBlack)];
+    let record = SgfRecord::create_with_steps(15, GrRenju, &steps);
+
+    assert_eq!(record.write(), "(;GM[4]FF[4]CA[UTF-8]SZ[15]RU[Renju];B[hh];W[ig];B[ao])\n");
+    assert_eq!(record.main_line(), steps.to_vec());
+    assert_eq!(SgfRecord::create_with_steps(15, GrFreestyle, &[]).write(),
+               "(;GM[4]FF[4]CA[UTF-8]SZ[15]RU[Freestyle])\n");
+}
+
+#[test]
+fn round_trip() {
+    let steps = [step(7, 7, CtBlack), step(6, 8, CtWhite), step(8, 8, CtBlack)];
+    let mut record = SgfRecord::create_with_steps(15, GrStandard, &steps);
+    record.root.comment = Some("a [test] \\ game".to_string());
+
+    // a variation after the first move, with a line of its own
+    let mut variation = SgfNode::create_with_step(step(8, 6, CtWhite));
+    variation.comment = Some("the other way".to_string());
+    variation.children.push(SgfNode::create_with_step(step(9, 5, CtBlack)));
+    record.root.children[0].children.push(variation);
+
+    let text = record.write();
+    assert_eq!(text, "(;GM[4]FF[4]CA[UTF-8]SZ[15]RU[Standard]C[a [test\\] \\\\ game];B[hh]\
+                      (;W[ig];B[ii])(;W[gi]C[the other way];B[fj]))\n");
+
+    let record_r = SgfRecord::parse(&text).unwrap();
+    assert_eq!(record_r, record);
+    assert_eq!(record_r.main_line(), steps.to_vec());
+    assert_eq!(record_r.root.children[0].children[1].main_line(),
+               vec![step(8, 6, CtWhite), step(9, 5, CtBlack)]);
+}
+
+#[test]
+fn parse() {
+    let text = "\u{feff}(;FF[4]GM[4]SZ[19]PB[black]PW[white]RU[renju]\n\
+                AP[some editor:1.0]C[first\\\nline]B[jj]\n;W[kk]  ;B[aa]\n)(;GM[4];B[hh])";
+    let record = SgfRecord::parse(text).unwrap();
+
+    assert_eq!(record.board_size, 19);
+    assert_eq!(record.rule, GrRenju);
+    assert_eq!(record.root.comment, Some("firstline".to_string()));
+    assert_eq!(record.main_line(), vec![step(9, 9, CtBlack), step(10, 10, CtWhite),
+                                        step(0, 0, CtBlack)]);
+
+    let record = SgfRecord::parse("(;GM[4]RU[Gomoku Pro];B[hh])").unwrap();
+    assert_eq!(record.board_size, 15);
+    assert_eq!(record.rule, GrFreestyle);
+}
+
+#[test]
+fn parse_invalid() {
+    let texts = [
+        "",
+        "(;GM[1]SZ[19];B[dd])",
+        "(;GM[4]SZ[15];B[hp])",
+        "(;GM[4]SZ[27])",
+        "(;GM[4];B[])",
+        "(;GM[4];B[hh]W[ii])",
+        "(;GM[4];B[hh]",
+        "(;GM[4];C[unclosed)",
+        "(;GM[4];B)",
+        "()",
+    ];
+
+    for text in texts.iter() {
+        assert_eq!(SgfRecord::parse(text).unwrap_err().kind, ErrorKind::RecordInvalid);
+    }
+}
+
+#[test]
+fn setup() {
+    let mut record = SgfRecord::create_with_steps(15, GrFreestyle, &[step(8, 8, CtWhite)]);
+    record.setup = vec![step(7, 7, CtBlack), step(0, 1, CtWhite), step(7, 8, CtBlack)];
+    record.first_chess = CtWhite;
+
+    let text = record.write();
+    assert_eq!(text, "(;GM[4]FF[4]CA[UTF-8]SZ[15]RU[Freestyle]AB[hh][ih]AW[ba]PL[W];W[ii])\n");
+
+    let record_r = SgfRecord::parse(&text).unwrap();
+    assert_eq!(record_r.setup, vec![step(7, 7, CtBlack), step(7, 8, CtBlack), step(0, 1, CtWhite)]);
+    assert_eq!(record_r.first_chess, CtWhite);
+    assert_eq!(record_r.main_line(), vec![step(8, 8, CtWhite)]);
+
+    assert_eq!(SgfRecord::parse("(;GM[4]PL[X])").unwrap_err().kind, ErrorKind::RecordInvalid);
+    assert_eq!(SgfRecord::parse("(;GM[4]AB[hh][zz])").unwrap_err().kind, ErrorKind::RecordInvalid);
+}
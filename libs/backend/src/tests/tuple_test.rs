@@ -12,8 +12,8 @@ fn it_works() {
     for row in 0..5 {                                                            // X O _ X O
     for col in 0..5 {                                                            // _ X O _ X
             match (row * 5 + col) % 3 {                                          // O _ X O _
-                0 => board.put_chess_at(Coord{row, col}, ChessType::CtBlack),    // X O _ X O
-                1 => board.put_chess_at(Coord{row, col}, ChessType::CtWhite),    // _ X O _ X
+                0 => board.put_chess_at(Coord{row, col}, CtBlack).unwrap(),      // X O _ X O
+                1 => board.put_chess_at(Coord{row, col}, CtWhite).unwrap(),      // _ X O _ X
                 _ => {},
             }
         }
@@ -29,53 +29,53 @@ fn it_works() {
     let tuple_r3 = Tuple::create_with_md(5, board.clone(), Coord{row: 4, col: 4}, MdUpLeft);
     let tuple_r4 = Tuple::create_with_md(5, board.clone(), Coord{row: 4, col: 0}, MdUpRight);
 
-    assert_eq!(tuple1.get_cross_point_type_at(0), CptChess(CtBlack));
-    assert_eq!(tuple1.get_cross_point_type_at(2), CptEmpty);
-    assert_eq!(tuple1.get_cross_point_type_at(1), CptChess(CtWhite));
-    assert_eq!(tuple1.get_cross_point_type_at(3), CptChess(CtBlack));
-    assert_eq!(tuple1.get_cross_point_type_at(4), CptChess(CtWhite));
+    assert_eq!(tuple1.get_cross_point_type_at(0).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple1.get_cross_point_type_at(2).unwrap(), CptEmpty);
+    assert_eq!(tuple1.get_cross_point_type_at(1).unwrap(), CptChess(CtWhite));
+    assert_eq!(tuple1.get_cross_point_type_at(3).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple1.get_cross_point_type_at(4).unwrap(), CptChess(CtWhite));
 
-    assert_eq!(tuple2.get_cross_point_type_at(0), CptChess(CtBlack));
-    assert_eq!(tuple2.get_cross_point_type_at(1), CptEmpty);
-    assert_eq!(tuple2.get_cross_point_type_at(2), CptChess(CtWhite));
-    assert_eq!(tuple2.get_cross_point_type_at(3), CptChess(CtBlack));
-    assert_eq!(tuple2.get_cross_point_type_at(4), CptEmpty);
+    assert_eq!(tuple2.get_cross_point_type_at(0).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple2.get_cross_point_type_at(1).unwrap(), CptEmpty);
+    assert_eq!(tuple2.get_cross_point_type_at(2).unwrap(), CptChess(CtWhite));
+    assert_eq!(tuple2.get_cross_point_type_at(3).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple2.get_cross_point_type_at(4).unwrap(), CptEmpty);
 
-    assert_eq!(tuple3.get_cross_point_type_at(0), CptChess(CtBlack));
-    assert_eq!(tuple3.get_cross_point_type_at(1), CptChess(CtBlack));
-    assert_eq!(tuple3.get_cross_point_type_at(2), CptChess(CtBlack));
-    assert_eq!(tuple3.get_cross_point_type_at(3), CptChess(CtBlack));
-    assert_eq!(tuple3.get_cross_point_type_at(4), CptChess(CtBlack));
+    assert_eq!(tuple3.get_cross_point_type_at(0).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple3.get_cross_point_type_at(1).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple3.get_cross_point_type_at(2).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple3.get_cross_point_type_at(3).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple3.get_cross_point_type_at(4).unwrap(), CptChess(CtBlack));
 
-    assert_eq!(tuple4.get_cross_point_type_at(0), CptChess(CtWhite));
-    assert_eq!(tuple4.get_cross_point_type_at(1), CptEmpty);
-    assert_eq!(tuple4.get_cross_point_type_at(2), CptChess(CtBlack));
-    assert_eq!(tuple4.get_cross_point_type_at(3), CptChess(CtWhite));
-    assert_eq!(tuple4.get_cross_point_type_at(4), CptEmpty);
+    assert_eq!(tuple4.get_cross_point_type_at(0).unwrap(), CptChess(CtWhite));
+    assert_eq!(tuple4.get_cross_point_type_at(1).unwrap(), CptEmpty);
+    assert_eq!(tuple4.get_cross_point_type_at(2).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple4.get_cross_point_type_at(3).unwrap(), CptChess(CtWhite));
+    assert_eq!(tuple4.get_cross_point_type_at(4).unwrap(), CptEmpty);
 
-    assert_eq!(tuple_r1.get_cross_point_type_at(0), CptChess(CtBlack));
-    assert_eq!(tuple_r1.get_cross_point_type_at(1), CptEmpty);
-    assert_eq!(tuple_r1.get_cross_point_type_at(2), CptChess(CtWhite));
-    assert_eq!(tuple_r1.get_cross_point_type_at(3), CptChess(CtBlack));
-    assert_eq!(tuple_r1.get_cross_point_type_at(4), CptEmpty);
+    assert_eq!(tuple_r1.get_cross_point_type_at(0).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple_r1.get_cross_point_type_at(1).unwrap(), CptEmpty);
+    assert_eq!(tuple_r1.get_cross_point_type_at(2).unwrap(), CptChess(CtWhite));
+    assert_eq!(tuple_r1.get_cross_point_type_at(3).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple_r1.get_cross_point_type_at(4).unwrap(), CptEmpty);
 
-    assert_eq!(tuple_r2.get_cross_point_type_at(0), CptChess(CtBlack));
-    assert_eq!(tuple_r2.get_cross_point_type_at(1), CptChess(CtWhite));
-    assert_eq!(tuple_r2.get_cross_point_type_at(2), CptEmpty);
-    assert_eq!(tuple_r2.get_cross_point_type_at(3), CptChess(CtBlack));
-    assert_eq!(tuple_r2.get_cross_point_type_at(4), CptChess(CtWhite));
+    assert_eq!(tuple_r2.get_cross_point_type_at(0).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple_r2.get_cross_point_type_at(1).unwrap(), CptChess(CtWhite));
+    assert_eq!(tuple_r2.get_cross_point_type_at(2).unwrap(), CptEmpty);
+    assert_eq!(tuple_r2.get_cross_point_type_at(3).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple_r2.get_cross_point_type_at(4).unwrap(), CptChess(CtWhite));
 
-    assert_eq!(tuple_r3.get_cross_point_type_at(0), CptChess(CtBlack));
-    assert_eq!(tuple_r3.get_cross_point_type_at(1), CptChess(CtBlack));
-    assert_eq!(tuple_r3.get_cross_point_type_at(2), CptChess(CtBlack));
-    assert_eq!(tuple_r3.get_cross_point_type_at(3), CptChess(CtBlack));
-    assert_eq!(tuple_r3.get_cross_point_type_at(4), CptChess(CtBlack));
+    assert_eq!(tuple_r3.get_cross_point_type_at(0).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple_r3.get_cross_point_type_at(1).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple_r3.get_cross_point_type_at(2).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple_r3.get_cross_point_type_at(3).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple_r3.get_cross_point_type_at(4).unwrap(), CptChess(CtBlack));
 
-    assert_eq!(tuple_r4.get_cross_point_type_at(0), CptEmpty);
-    assert_eq!(tuple_r4.get_cross_point_type_at(1), CptChess(CtWhite));
-    assert_eq!(tuple_r4.get_cross_point_type_at(2), CptChess(CtBlack));
-    assert_eq!(tuple_r4.get_cross_point_type_at(3), CptEmpty);
-    assert_eq!(tuple_r4.get_cross_point_type_at(4), CptChess(CtWhite));
+    assert_eq!(tuple_r4.get_cross_point_type_at(0).unwrap(), CptEmpty);
+    assert_eq!(tuple_r4.get_cross_point_type_at(1).unwrap(), CptChess(CtWhite));
+    assert_eq!(tuple_r4.get_cross_point_type_at(2).unwrap(), CptChess(CtBlack));
+    assert_eq!(tuple_r4.get_cross_point_type_at(3).unwrap(), CptEmpty);
+    assert_eq!(tuple_r4.get_cross_point_type_at(4).unwrap(), CptChess(CtWhite));
 
     assert_eq!(tuple1.count(CptChess(CtBlack)), 2);
     assert_eq!(tuple1.count(CptChess(CtWhite)), 2);
@@ -105,44 +105,44 @@ fn it_works() {
     for row in 0..5 {
         for col in 0..5 {
             if row == 0 {
-                assert_eq!(tuple1.have_include(row, col), true);
+                assert_eq!(tuple1.have_include(Coord{row, col}), true);
             } else {
-                assert_eq!(tuple1.have_include(row, col), false);
+                assert_eq!(tuple1.have_include(Coord{row, col}), false);
             }
             if col == 0 {
-                assert_eq!(tuple2.have_include(row, col), true);
+                assert_eq!(tuple2.have_include(Coord{row, col}), true);
             } else {
-                assert_eq!(tuple2.have_include(row, col), false);
+                assert_eq!(tuple2.have_include(Coord{row, col}), false);
             }
             if row == col {
-                assert_eq!(tuple3.have_include(row, col), true);
+                assert_eq!(tuple3.have_include(Coord{row, col}), true);
             } else {
-                assert_eq!(tuple3.have_include(row, col), false);
+                assert_eq!(tuple3.have_include(Coord{row, col}), false);
             }
             if row + col == 4 {
-                assert_eq!(tuple4.have_include(row, col), true);
+                assert_eq!(tuple4.have_include(Coord{row, col}), true);
             } else {
-                assert_eq!(tuple4.have_include(row, col), false);
+                assert_eq!(tuple4.have_include(Coord{row, col}), false);
             }
             if row == 4 {
-                assert_eq!(tuple_r1.have_include(row, col), true);
+                assert_eq!(tuple_r1.have_include(Coord{row, col}), true);
             } else {
-                assert_eq!(tuple_r1.have_include(row, col), false);
+                assert_eq!(tuple_r1.have_include(Coord{row, col}), false);
             }
             if col == 4 {
-                assert_eq!(tuple_r2.have_include(row, col), true);
+                assert_eq!(tuple_r2.have_include(Coord{row, col}), true);
             } else {
-                assert_eq!(tuple_r2.have_include(row, col), false);
+                assert_eq!(tuple_r2.have_include(Coord{row, col}), false);
             }
             if row == col {
-                assert_eq!(tuple_r3.have_include(row, col), true);
+                assert_eq!(tuple_r3.have_include(Coord{row, col}), true);
             } else {
-                assert_eq!(tuple_r3.have_include(row, col), false);
+                assert_eq!(tuple_r3.have_include(Coord{row, col}), false);
             }
             if row + col == 4 {
-                assert_eq!(tuple_r4.have_include(row, col), true);
+                assert_eq!(tuple_r4.have_include(Coord{row, col}), true);
             } else {
-                assert_eq!(tuple_r4.have_include(row, col), false);
+                assert_eq!(tuple_r4.have_include(Coord{row, col}), false);
             }
         }
     }
@@ -0,0 +1,70 @@
+use super::super::board::*;
+use super::super::position::*;
+use super::super::zobrist::*;
+
+use super::super::cross_point::ChessType::*;
+
+#[test]
+fn transform() {
+    let coord = Coord{row: 1, col: 3};
+    let coords: Vec<Coord> = (0..SYMMETRY_COUNT)
+        .map(|symmetry| transform_coord(coord, 15, symmetry)).collect();
+    assert_eq!(coords, vec![Coord{row: 1, col: 3}, Coord{row: 3, col: 13}, Coord{row: 13, col: 11},
+                            Coord{row: 11, col: 1}, Coord{row: 1, col: 11}, Coord{row: 11, col: 13},
+                            Coord{row: 13, col: 3}, Coord{row: 3, col: 1}]);
+
+    let center = Coord{row: 7, col: 7};
+    for symmetry in 0..SYMMETRY_COUNT {
+        assert_eq!(transform_coord(center, 15, symmetry), center);
+    }
+}
+
+#[test]
+fn incremental_hash() {
+    let table = ZobristTable::create_with_size(15);
+    let board = Board::new();
+    assert_eq!(board.hash(), 0);
+
+    board.put_chess_at(Coord{row: 7, col: 7}, CtBlack).unwrap();
+    assert_eq!(board.hash(), table.key(Coord{row: 7, col: 7}, CtBlack));
+    board.put_chess_at(Coord{row: 3, col: 9}, CtWhite).unwrap();
+    assert_eq!(board.hash(), table.key(Coord{row: 7, col: 7}, CtBlack)
+                             ^ table.key(Coord{row: 3, col: 9}, CtWhite));
+
+    // the same chess in another order hash the same
+    let board_r = Board::new();
+    board_r.put_chess_at(Coord{row: 3, col: 9}, CtWhite).unwrap();
+    board_r.put_chess_at(Coord{row: 7, col: 7}, CtBlack).unwrap();
+    assert_eq!(board_r.hash(), board.hash());
+
+    assert_ne!(table.key(Coord{row: 7, col: 7}, CtBlack), table.key(Coord{row: 7, col: 7}, CtWhite));
+    assert_eq!(board.put_chess_at(Coord{row: 7, col: 7}, CtWhite).is_err(), true);
+    board.remove_chess_at(Coord{row: 3, col: 9}).unwrap();
+    board.remove_chess_at(Coord{row: 7, col: 7}).unwrap();
+    assert_eq!(board.hash(), 0);
+    assert_eq!(board.canonical_hash(), 0);
+}
+
+#[test]
+fn canonical_hash() {
+    let position: Position = "15 15/3x11/15/3o11/15/15/15/7x7/15/15/15/15/15/15/15 b".parse().unwrap();
+    let board = position.to_board();
+
+    let mut hashes = Vec::new();
+    for symmetry in 0..SYMMETRY_COUNT {
+        let board_s = Board::new();
+        for stone in position.stones().iter() {
+            board_s.put_chess_at(transform_coord(stone.coord, 15, symmetry), stone.chess).unwrap();
+        }
+        assert_eq!(board_s.canonical_hash(), board.canonical_hash());
+        hashes.push(board_s.hash());
+    }
+
+    hashes.sort();
+    hashes.dedup();
+    assert_eq!(hashes.len(), SYMMETRY_COUNT);
+
+    // swapping the colors is not a symmetry
+    let position: Position = "15 15/3o11/15/3x11/15/15/15/7x7/15/15/15/15/15/15/15 b".parse().unwrap();
+    assert_ne!(position.to_board().canonical_hash(), board.canonical_hash());
+}
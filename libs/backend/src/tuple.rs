@@ -2,6 +2,7 @@ use std::rc::*;
 
 use cross_point::*;
 use board::*;
+use utils::*;
 
 pub struct Tuple {
     size: usize,
@@ -23,36 +24,28 @@ impl Tuple {
         return Tuple { size, coords, cross_points };
     }
 
-    pub fn coord_at(&self, index: usize) -> Coord {
-        return self.coords[index];
+    pub fn coord_at(&self, index: usize) -> Result<Coord, Error> {
+        self.check_index(index)?;
+        return Ok(self.coords[index]);
     }
 
     pub fn is_index_valid(&self, index: usize) -> bool {
         return index < self.size;
     }
 
-    pub fn have_chess_at(&self, index: usize) -> bool {
-        if !self.is_index_valid(index) {
-            panic!("index out of range")
-        }
-
-        return self.cross_points[index].have_chess();
+    pub fn have_chess_at(&self, index: usize) -> Result<bool, Error> {
+        self.check_index(index)?;
+        return Ok(self.cross_points[index].have_chess());
     }
 
-    pub fn get_chess_at(&self, index: usize) -> ChessType {
-        if !self.is_index_valid(index) {
-            panic!("index out of range")
-        }
-
+    pub fn get_chess_at(&self, index: usize) -> Result<ChessType, Error> {
+        self.check_index(index)?;
         return self.cross_points[index].get_chess();
     }
 
-    pub fn get_cross_point_type_at(&self, index: usize) -> CrossPointType {
-        if !self.is_index_valid(index) {
-            panic!("index out of range")
-        }
-
-        return self.cross_points[index].get_cross_point_type();
+    pub fn get_cross_point_type_at(&self, index: usize) -> Result<CrossPointType, Error> {
+        self.check_index(index)?;
+        return Ok(self.cross_points[index].get_cross_point_type());
     }
 
     pub fn count(&self, cpt: CrossPointType) -> u32 {
@@ -75,4 +68,12 @@ impl Tuple {
 
         return false;
     }
+
+    fn check_index(&self, index: usize) -> Result<(), Error> {
+        match self.is_index_valid(index) {
+            true => return Ok(()),
+            false => return Err(Error::create_with_detail(ErrorKind::CoordInvalid,
+                                                          "index out of range")),
+        }
+    }
 }
\ No newline at end of file
@@ -1,6 +1,20 @@
+use forbidden_checker::ForbiddenType;
+
 #[derive(PartialEq, Debug)]
 pub enum ErrorKind {
     CoordInvalid,
+    CellOccupied,
+    CellEmpty,
+    GameNotStarted,
+    GameAlreadyStarted,
+    GameAlreadyOver,
+    WrongTurn,
+    MoveForbidden(ForbiddenType),
+    OpeningActionInvalid,
+    RecordInvalid,
+    IoFailed,
+    NodeNotFound,
+    PositionInvalid,
 }
 
 #[derive(PartialEq, Debug)]
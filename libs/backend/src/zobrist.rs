@@ -0,0 +1,69 @@
+use board::*;
+use cross_point::*;
+
+use cross_point::ChessType::*;
+
+// four rotations, each of them with and without a reflection
+pub const SYMMETRY_COUNT: usize = 8;
+
+// the keys come from a fixed seed, so boards of the same size hash the
+// same position to the same value in every run
+const ZOBRIST_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// a random key for every cross point and chess, the hash of a position
+// is the xor of the keys of its chess, the empty board hashes to 0
+pub struct ZobristTable {
+    size: usize,
+    keys: Vec<u64>,
+}
+
+impl ZobristTable {
+    pub fn create_with_size(size: usize) -> ZobristTable {
+        let mut state = ZOBRIST_SEED;
+        let keys = (0..size * size * 2).map(|_| split_mix(&mut state)).collect();
+        return ZobristTable { size, keys };
+    }
+
+    pub fn key(&self, coord: Coord, chess: ChessType) -> u64 {
+        let chess_index = match chess {
+            CtBlack => 0,
+            CtWhite => 1,
+        };
+        return self.keys[(coord.row * self.size + coord.col) * 2 + chess_index];
+    }
+
+    // the key of the chess as seen in each symmetry of the board, the
+    // first one is the board as it is
+    pub fn symmetry_keys(&self, coord: Coord, chess: ChessType) -> [u64; SYMMETRY_COUNT] {
+        let mut keys = [0; SYMMETRY_COUNT];
+        for symmetry in 0..SYMMETRY_COUNT {
+            keys[symmetry] = self.key(transform_coord(coord, self.size, symmetry), chess);
+        }
+
+        return keys;
+    }
+}
+
+// symmetry 0 to 3 rotate the board clockwise that many times, 4 to 7
+// mirror the columns first
+pub fn transform_coord(coord: Coord, size: usize, symmetry: usize) -> Coord {
+    let (mut row, mut col) = (coord.row, coord.col);
+    if symmetry >= 4 {
+        col = size - 1 - col;
+    }
+    for _ in 0..symmetry % 4 {
+        let old_row = row;
+        row = col;
+        col = size - 1 - old_row;
+    }
+
+    return Coord{row, col};
+}
+
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    return z ^ (z >> 31);
+}
@@ -0,0 +1,31 @@
+extern crate bot;
+
+use std::io;
+use std::io::{BufRead, Write};
+
+use bot::protocol::*;
+
+// the gomocup managers look for engines named pbrain-*
+fn main() {
+    let engine = PiskvorkEngine::new();
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+
+        // the manager waits for each answer, so it is flushed at once
+        let mut out = stdout.lock();
+        for answer in engine.handle_line(&line) {
+            writeln!(out, "{}", answer).unwrap();
+        }
+        out.flush().unwrap();
+
+        if engine.is_finished() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::cell::Cell;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use self::Difficulty::*;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Difficulty {
+    DfEasy,
+    DfNormal,
+    DfHard,
+}
+
+// how far the bot looks and how much it is made to play worse on purpose
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct DifficultyProfile {
+    pub max_depth: usize,
+    pub max_candidates: usize,
+    // the deepening stops once either is used up, the last finished depth
+    // gives the move
+    pub node_budget: Option<u64>,
+    pub time_budget: Option<Duration>,
+    // every leaf evaluation is moved by up to this much either way
+    pub noise: i32,
+    // the chance of playing one of the better looking candidates of the
+    // root instead of the searched move, a five and blocking one are kept
+    pub blunder_rate: f64,
+    // forced wins by continuous fours are looked for before searching,
+    // within this many nodes of the solver
+    pub solve_threats: bool,
+    pub solver_node_budget: u64,
+    // the forced wins may also go through threes
+    pub solve_vct: bool,
+}
+
+impl Difficulty {
+    pub fn profile(&self) -> DifficultyProfile {
+        match *self {
+            DfEasy => return DifficultyProfile {
+                max_depth: 2,
+                max_candidates: 8,
+                node_budget: Some(2_000),
+                time_budget: Some(Duration::from_millis(200)),
+                noise: 2_000,
+                blunder_rate: 0.25,
+                solve_threats: false,
+                solver_node_budget: 0,
+                solve_vct: false,
+            },
+            DfNormal => return DifficultyProfile {
+                max_depth: 3,
+                max_candidates: 12,
+                node_budget: Some(50_000),
+                time_budget: Some(Duration::from_secs(1)),
+                noise: 300,
+                blunder_rate: 0.05,
+                solve_threats: true,
+                solver_node_budget: 10_000,
+                solve_vct: false,
+            },
+            DfHard => return DifficultyProfile {
+                max_depth: 4,
+                max_candidates: 15,
+                node_budget: None,
+                time_budget: None,
+                noise: 0,
+                blunder_rate: 0.0,
+                solve_threats: true,
+                solver_node_budget: 20_000,
+                solve_vct: true,
+            },
+        }
+    }
+}
+
+// the noise and the blunders need no good randomness, a seed from the
+// clock makes two games differ and a fixed one keeps tests repeatable
+pub struct Random {
+    state: Cell<u64>,
+}
+
+impl Random {
+    pub fn new() -> Random {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|time| time.subsec_nanos() as u64 ^ time.as_secs())
+            .unwrap_or(0);
+        return Random::create_with_seed(nanos);
+    }
+
+    pub fn create_with_seed(seed: u64) -> Random {
+        return Random { state: Cell::new(seed) };
+    }
+
+    pub fn set_seed(&self, seed: u64) {
+        self.state.set(seed);
+    }
+
+    pub fn next(&self) -> u64 {
+        let state = self.state.get().wrapping_add(0x9e37_79b9_7f4a_7c15);
+        self.state.set(state);
+
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        return z ^ (z >> 31);
+    }
+
+    // 0 until bound, bound must not be 0
+    pub fn below(&self, bound: u64) -> u64 {
+        return self.next() % bound;
+    }
+
+    // between -range and range
+    pub fn spread(&self, range: i32) -> i32 {
+        if range <= 0 {
+            return 0;
+        }
+        return self.below(range as u64 * 2 + 1) as i32 - range;
+    }
+
+    pub fn chance(&self, probability: f64) -> bool {
+        if probability <= 0.0 {
+            return false;
+        }
+        let fraction = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+        return fraction < probability;
+    }
+}
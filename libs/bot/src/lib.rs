@@ -1,7 +1,11 @@
+extern crate backend;
+
+pub mod difficulty;
+pub mod players;
+pub mod protocol;
+pub mod search;
+pub mod threat;
+pub mod transposition;
+
 #[cfg(test)]
-mod tests {
-    #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
-    }
-}
+mod tests;
@@ -0,0 +1,105 @@
+use std::rc::Rc;
+use std::cell::Cell;
+
+use backend::board::*;
+use backend::cross_point::*;
+use backend::game_session::*;
+use backend::utils::*;
+
+use search::*;
+
+use backend::cross_point::ChessType::*;
+use self::PlayerKind::*;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum PlayerKind {
+    PkHuman,
+    PkBot,
+}
+
+// who plays each color of a game session, the front ends leave the turns
+// to it so a human never moves for the bot and the bot only moves on its
+// own turn
+pub struct Players {
+    session: Rc<GameSession>,
+    bot: Bot,
+    black: Cell<PlayerKind>,
+    white: Cell<PlayerKind>,
+}
+
+impl Players {
+    pub fn create_with_session(session: Rc<GameSession>, black: PlayerKind,
+                               white: PlayerKind) -> Players {
+        let bot = Bot::create_with_rule(session.board(), session.rule());
+
+        return Players {
+            session,
+            bot,
+            black: Cell::new(black),
+            white: Cell::new(white),
+        };
+    }
+
+    pub fn session(&self) -> &GameSession {
+        return &self.session;
+    }
+
+    pub fn bot(&self) -> &Bot {
+        return &self.bot;
+    }
+
+    pub fn player_of(&self, chess: ChessType) -> PlayerKind {
+        match chess {
+            CtBlack => return self.black.get(),
+            CtWhite => return self.white.get(),
+        }
+    }
+
+    pub fn set_players(&self, black: PlayerKind, white: PlayerKind) {
+        self.black.set(black);
+        self.white.set(white);
+    }
+
+    // the opening and the setup are left to the humans
+    pub fn is_bot_to_move(&self) -> bool {
+        return self.session.is_opening_finished() && !self.session.is_in_setup()
+            && !self.session.is_over() && self.player_of(self.session.next_chess()) == PkBot;
+    }
+
+    // None when it is not the turn of the bot or it finds no move
+    pub fn play_bot_move(&self) -> Result<Option<CoordAndChess>, Error> {
+        if !self.is_bot_to_move() {
+            return Ok(None);
+        }
+
+        let chess = self.session.next_chess();
+        let coord = match self.bot.search(chess).best_move {
+            Some(coord) => coord,
+            None => return Ok(None),
+        };
+
+        self.session.put_chess(coord)?;
+        return Ok(Some(CoordAndChess{coord, chess}));
+    }
+
+    pub fn put_human_chess(&self, coord: Coord) -> Result<(), Error> {
+        if self.is_bot_to_move() {
+            return Err(Error::create_with_detail(ErrorKind::WrongTurn, "it is the bot's turn"));
+        }
+
+        return self.session.put_chess(coord);
+    }
+
+    // a human playing the bot takes back the reply of the bot together
+    // with the own move
+    pub fn take_back_human_move(&self) -> Result<(), Error> {
+        match (self.black.get(), self.white.get()) {
+            (PkHuman, PkBot) => return self.session.undo_to_my_last_move(CtBlack),
+            (PkBot, PkHuman) => return self.session.undo_to_my_last_move(CtWhite),
+            _ => {
+                self.session.take_back()?;
+                return Ok(());
+            },
+        }
+    }
+}
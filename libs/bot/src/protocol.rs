@@ -0,0 +1,352 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use backend::board::*;
+use backend::game_session::*;
+use backend::rule_checker::*;
+use backend::utils::*;
+
+use search::*;
+use transposition::*;
+
+use backend::cross_point::ChessType::*;
+use backend::rule_checker::GameRule::*;
+
+// five in a row needs a board of five at least, and the packed positions
+// and the notation stop at 26 columns
+const MIN_BOARD_SIZE: usize = 5;
+const MAX_BOARD_SIZE: usize = 26;
+// the share of timeout_turn the search may use
+const TURN_TIME_PERCENT: u64 = 80;
+// a move takes at most this part of the time left in the match
+const MATCH_TIME_DIVISOR: u64 = 20;
+// without a time limit the depth of the bot is kept
+const UNTIMED_MAX_DEPTH: usize = 4;
+const TIMED_MAX_DEPTH: usize = 32;
+
+const ABOUT: &'static str = "name=\"gomoku\", version=\"0.1.0\", author=\"Ho1messi\", country=\"China\"";
+
+// the piskvork protocol of gomocup, one command a line on stdin and the
+// answers on stdout, the coords are "x,y" with x the column
+//
+// START 15      -> OK
+// BEGIN         -> 7,7
+// TURN 8,7      -> 8,8
+// BOARD ... DONE, where every line is "x,y,1" for the own chess and
+// "x,y,2" for the opponent's
+// INFO rule 4   -> the rule of the next game, or of this one before a move
+pub struct PiskvorkEngine {
+    session: RefCell<Option<Rc<GameSession>>>,
+    bot: RefCell<Option<Bot>>,
+    // the stones read after BOARD, put on the board with DONE
+    board_lines: RefCell<Option<Vec<(Coord, bool)>>>,
+    rule: Cell<GameRule>,
+    timeout_turn: Cell<Option<u64>>,
+    // 0 for no limit of the match
+    timeout_match: Cell<Option<u64>>,
+    time_left: Cell<Option<u64>>,
+    max_memory: Cell<Option<u64>>,
+    finished: Cell<bool>,
+}
+
+impl PiskvorkEngine {
+    pub fn new() -> PiskvorkEngine {
+        return PiskvorkEngine {
+            session: RefCell::new(None),
+            bot: RefCell::new(None),
+            board_lines: RefCell::new(None),
+            rule: Cell::new(GrFreestyle),
+            timeout_turn: Cell::new(None),
+            timeout_match: Cell::new(None),
+            time_left: Cell::new(None),
+            max_memory: Cell::new(None),
+            finished: Cell::new(false),
+        };
+    }
+
+    // END was read and nothing more should be
+    pub fn is_finished(&self) -> bool {
+        return self.finished.get();
+    }
+
+    // the lines to write back, none for the commands which have no answer
+    pub fn handle_line(&self, line: &str) -> Vec<String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Vec::new();
+        }
+
+        if self.board_lines.borrow().is_some() {
+            return answer_lines(self.handle_board_line(line));
+        }
+
+        let (command, argument) = match line.find(' ') {
+            Some(index) => (&line[..index], line[index + 1..].trim()),
+            None => (line, ""),
+        };
+        let result = match command.to_uppercase().as_str() {
+            "START" => self.start(argument).map(Some),
+            "RESTART" => self.restart().map(Some),
+            "BEGIN" => self.begin().map(Some),
+            "TURN" => self.turn(argument).map(Some),
+            "BOARD" => self.begin_board().map(|_| None),
+            "TAKEBACK" => self.take_back(argument).map(Some),
+            "INFO" => self.info(argument).map(|_| None),
+            "ABOUT" => Ok(Some(ABOUT.to_string())),
+            "END" => {
+                self.finished.set(true);
+                Ok(None)
+            },
+            _ => return vec![format!("UNKNOWN {}", command)],
+        };
+
+        return answer_lines(result);
+    }
+
+    fn start(&self, argument: &str) -> Result<String, Error> {
+        let size = match argument.parse::<usize>() {
+            Ok(size) if size >= MIN_BOARD_SIZE && size <= MAX_BOARD_SIZE => size,
+            _ => return Err(Error::create_with_detail(ErrorKind::PositionInvalid,
+                                                      "unsupported board size")),
+        };
+
+        self.new_session(size);
+        return Ok("OK".to_string());
+    }
+
+    fn restart(&self) -> Result<String, Error> {
+        let session = self.session()?;
+        match session.rule() == self.rule.get() {
+            true => session.restart()?,
+            false => self.new_session(session.board_size()),
+        }
+        *self.board_lines.borrow_mut() = None;
+        return Ok("OK".to_string());
+    }
+
+    fn begin(&self) -> Result<String, Error> {
+        self.session()?;
+        return self.play();
+    }
+
+    // the opponent is the side to move, so the first TURN of a game makes
+    // the engine white
+    fn turn(&self, argument: &str) -> Result<String, Error> {
+        let session = self.session()?;
+        session.put_chess(parse_coord(argument, session.board_size())?)?;
+        return self.play();
+    }
+
+    fn begin_board(&self) -> Result<(), Error> {
+        self.session()?;
+        *self.board_lines.borrow_mut() = Some(Vec::new());
+        return Ok(());
+    }
+
+    fn handle_board_line(&self, line: &str) -> Result<Option<String>, Error> {
+        if line.to_uppercase() != "DONE" {
+            let size = self.session()?.board_size();
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            if fields.len() != 3 {
+                return Err(invalid_coord());
+            }
+
+            let coord = parse_coord(&format!("{},{}", fields[0], fields[1]), size)?;
+            let own = match fields[2] {
+                "1" => true,
+                // a 3 is a stone of the opponent in the continuous game
+                "2" | "3" => false,
+                _ => return Err(invalid_coord()),
+            };
+            self.board_lines.borrow_mut().as_mut().unwrap().push((coord, own));
+            return Ok(None);
+        }
+
+        let lines = self.board_lines.borrow_mut().take().unwrap();
+        let session = self.session()?;
+        session.restart()?;
+
+        // the side to move has as many stones as the other one, or one less
+        let own_count = lines.iter().filter(|line| line.1).count();
+        let own_chess = match own_count * 2 < lines.len() {
+            true => CtWhite,
+            false => CtBlack,
+        };
+
+        session.begin_setup()?;
+        for &(coord, own) in lines.iter() {
+            let chess = match own {
+                true => own_chess,
+                false => own_chess.get_different_chess(),
+            };
+            session.setup_chess(coord, Some(chess))?;
+        }
+        session.finish_setup(own_chess)?;
+
+        return self.play().map(Some);
+    }
+
+    // only the last move can be taken back, the session keeps no others apart
+    fn take_back(&self, argument: &str) -> Result<String, Error> {
+        let session = self.session()?;
+        let coord = parse_coord(argument, session.board_size())?;
+        match session.last_step() {
+            Some(step) if step.coord == coord => {
+                session.take_back()?;
+                return Ok("OK".to_string());
+            },
+            _ => return Err(Error::create_with_detail(ErrorKind::NodeNotFound,
+                                                      "only the last move can be taken back")),
+        }
+    }
+
+    // unknown keys are left alone, as the protocol asks
+    fn info(&self, argument: &str) -> Result<(), Error> {
+        let mut fields = argument.split_whitespace();
+        let key = fields.next().unwrap_or("").to_lowercase();
+        let value = fields.next().and_then(|value| value.parse::<u64>().ok());
+
+        match key.as_str() {
+            "timeout_turn" => self.timeout_turn.set(value),
+            "timeout_match" => self.timeout_match.set(value),
+            "time_left" => self.time_left.set(value),
+            "rule" => {
+                self.rule.set(rule_from_flags(value.unwrap_or(0)));
+                self.update_rule();
+            },
+            "max_memory" => {
+                self.max_memory.set(value);
+                if let Some(ref bot) = *self.bot.borrow() {
+                    bot.set_table_size_mb(self.table_size_mb());
+                }
+            },
+            _ => {},
+        }
+
+        return Ok(());
+    }
+
+    // the engine is always the side to move when it plays
+    fn play(&self) -> Result<String, Error> {
+        let session = self.session()?;
+        if session.is_over() {
+            return Err(Error::create_with_detail(ErrorKind::GameAlreadyOver,
+                                                 "the game is over"));
+        }
+
+        let bot = self.bot.borrow();
+        let bot = bot.as_ref().unwrap();
+        let time_budget = self.turn_budget();
+        bot.set_time_budget(time_budget);
+        match time_budget {
+            Some(_) => bot.set_max_depth(TIMED_MAX_DEPTH),
+            None => bot.set_max_depth(UNTIMED_MAX_DEPTH),
+        }
+
+        let coord = match bot.search(session.next_chess()).best_move {
+            Some(coord) => coord,
+            None => return Err(Error::create_with_detail(ErrorKind::GameAlreadyOver,
+                                                         "no cross point left")),
+        };
+
+        session.put_chess(coord)?;
+        return Ok(format!("{},{}", coord.col, coord.row));
+    }
+
+    // a game with moves keeps its rule, the next one takes the new rule
+    fn update_rule(&self) {
+        let size = match *self.session.borrow() {
+            Some(ref session) if session.rule() != self.rule.get()
+                && session.steps().is_empty() && session.setup_stones().is_empty() => {
+                session.board_size()
+            },
+            _ => return,
+        };
+
+        self.new_session(size);
+    }
+
+    fn new_session(&self, size: usize) {
+        let session = GameSession::create_with_size(size, self.rule.get());
+        let bot = Bot::create_with_rule(session.board(), session.rule());
+        bot.set_table_size_mb(self.table_size_mb());
+
+        *self.bot.borrow_mut() = Some(bot);
+        *self.session.borrow_mut() = Some(Rc::new(session));
+        *self.board_lines.borrow_mut() = None;
+    }
+
+    fn session(&self) -> Result<Rc<GameSession>, Error> {
+        return self.session.borrow().clone().ok_or_else(|| {
+            Error::create_with_detail(ErrorKind::GameNotStarted, "START has not been sent")
+        });
+    }
+
+    // the search deepens until the time is up, a part of the turn is kept
+    // for the threat solver and for answering, and the time left in the
+    // match is shared out among the moves to come
+    fn turn_budget(&self) -> Option<Duration> {
+        let turn_time = self.timeout_turn.get().map(|timeout| timeout * TURN_TIME_PERCENT / 100);
+        let match_time = match self.timeout_match.get() {
+            Some(timeout) if timeout > 0 => {
+                Some(self.time_left.get().unwrap_or(timeout) / MATCH_TIME_DIVISOR)
+            },
+            _ => None,
+        };
+
+        let budget = match (turn_time, match_time) {
+            (Some(turn_time), Some(match_time)) => Some(turn_time.min(match_time)),
+            (turn_time, match_time) => turn_time.or(match_time),
+        };
+        return budget.map(Duration::from_millis);
+    }
+
+    // the table takes half of the memory the manager allows
+    fn table_size_mb(&self) -> usize {
+        match self.max_memory.get() {
+            Some(memory) if memory > 0 => return (memory / 2 / 1024 / 1024) as usize,
+            _ => return DEFAULT_TABLE_SIZE_MB,
+        }
+    }
+}
+
+// the rule of INFO is a set of flags, 1 for exactly five, 2 for a
+// continuous game, 4 for renju and 8 for caro
+fn rule_from_flags(flags: u64) -> GameRule {
+    if flags & 4 != 0 {
+        return GrRenju;
+    } else if flags & 8 != 0 {
+        return GrCaro;
+    } else if flags & 1 != 0 {
+        return GrStandard;
+    }
+
+    return GrFreestyle;
+}
+
+fn parse_coord(text: &str, size: usize) -> Result<Coord, Error> {
+    let fields: Vec<&str> = text.split(',').map(|field| field.trim()).collect();
+    if fields.len() != 2 {
+        return Err(invalid_coord());
+    }
+
+    let col = fields[0].parse::<usize>().map_err(|_| invalid_coord())?;
+    let row = fields[1].parse::<usize>().map_err(|_| invalid_coord())?;
+    if row >= size || col >= size {
+        return Err(invalid_coord());
+    }
+
+    return Ok(Coord{row, col});
+}
+
+fn invalid_coord() -> Error {
+    return Error::create_with_detail(ErrorKind::CoordInvalid, "expected x,y on the board");
+}
+
+fn answer_lines(result: Result<Option<String>, Error>) -> Vec<String> {
+    match result {
+        Ok(answer) => return answer.into_iter().collect(),
+        Err(error) => return vec![format!("ERROR {}", error.message)],
+    }
+}
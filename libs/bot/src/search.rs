@@ -0,0 +1,511 @@
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+use backend::board::*;
+use backend::cross_point::*;
+use backend::rule_checker::*;
+
+use difficulty::*;
+use threat::*;
+use transposition::*;
+
+use backend::cross_point::ChessType::*;
+use backend::rule_checker::GameRule::*;
+use backend::rule_checker::GameStatus::*;
+use threat::SolveStatus::*;
+use transposition::BoundType::*;
+
+pub const WIN_SCORE: i32 = 1_000_000_000;
+const INFINITE_SCORE: i32 = WIN_SCORE + 1;
+pub const MAX_PLY: i32 = 64;
+
+const DEFAULT_MAX_DEPTH: usize = 4;
+const DEFAULT_MAX_CANDIDATES: usize = 15;
+const CANDIDATE_DISTANCE: usize = 2;
+// a forced win is looked for before searching, within this many nodes
+const DEFAULT_SOLVER_NODE_BUDGET: u64 = 20_000;
+// the clock is read once every this many nodes, a node scores all its
+// candidates and takes long enough
+const CLOCK_CHECK_NODES: u64 = 16;
+// a blunder picks among this many of the best looking candidates
+const BLUNDER_CANDIDATES: usize = 5;
+
+// the board hash leaves out the side to move, white to move flips it
+const WHITE_TO_MOVE_KEY: u64 = 0x6a09_e667_f3bc_c909;
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct SearchResult {
+    pub best_move: Option<Coord>,
+    pub score: i32,
+    pub principal_variation: Vec<Coord>,
+    pub depth: usize,
+    pub nodes: u64,
+}
+
+impl SearchResult {
+    pub fn is_win(&self) -> bool {
+        return self.score >= WIN_SCORE - MAX_PLY;
+    }
+
+    pub fn is_loss(&self) -> bool {
+        return self.score <= -WIN_SCORE + MAX_PLY;
+    }
+}
+
+pub struct Bot {
+    board: Rc<Board>,
+    rule_checker: Rc<RuleChecker>,
+    max_depth: Cell<usize>,
+    max_candidates: Cell<usize>,
+    nodes: Cell<u64>,
+    principal_variation: RefCell<Vec<Coord>>,
+    table: RefCell<TranspositionTable>,
+    node_budget: Cell<Option<u64>>,
+    time_budget: Cell<Option<Duration>>,
+    deadline: Cell<Option<Instant>>,
+    // set once a budget is used up, the depth being searched is dropped
+    aborted: Cell<bool>,
+    noise: Cell<i32>,
+    blunder_rate: Cell<f64>,
+    solve_threats: Cell<bool>,
+    solver_node_budget: Cell<u64>,
+    solve_vct: Cell<bool>,
+    random: Random,
+}
+
+impl Bot {
+    pub fn new(board: Rc<Board>) -> Bot {
+        return Bot::create_with_rule(board, GrFreestyle);
+    }
+
+    pub fn create_with_depth(board: Rc<Board>, max_depth: usize) -> Bot {
+        let bot = Bot::create_with_rule(board, GrFreestyle);
+        bot.set_max_depth(max_depth);
+        return bot;
+    }
+
+    // the bot wins and blocks by the rule, and black under renju never
+    // plays a forbidden move
+    pub fn create_with_rule(board: Rc<Board>, rule: GameRule) -> Bot {
+        let rule_checker = RuleChecker::create_with_rule(board.clone(), rule);
+
+        return Bot {
+            board,
+            rule_checker,
+            max_depth: Cell::new(DEFAULT_MAX_DEPTH),
+            max_candidates: Cell::new(DEFAULT_MAX_CANDIDATES),
+            nodes: Cell::new(0),
+            principal_variation: RefCell::new(Vec::new()),
+            table: RefCell::new(TranspositionTable::new()),
+            node_budget: Cell::new(None),
+            time_budget: Cell::new(None),
+            deadline: Cell::new(None),
+            aborted: Cell::new(false),
+            noise: Cell::new(0),
+            blunder_rate: Cell::new(0.0),
+            solve_threats: Cell::new(true),
+            solver_node_budget: Cell::new(DEFAULT_SOLVER_NODE_BUDGET),
+            solve_vct: Cell::new(true),
+            random: Random::new(),
+        };
+    }
+
+    // the limits and the weakening of the profile replace the ones before
+    pub fn set_difficulty(&self, difficulty: Difficulty) {
+        let profile = difficulty.profile();
+        self.max_depth.set(profile.max_depth);
+        self.max_candidates.set(profile.max_candidates);
+        self.node_budget.set(profile.node_budget);
+        self.time_budget.set(profile.time_budget);
+        self.noise.set(profile.noise);
+        self.blunder_rate.set(profile.blunder_rate);
+        self.solve_threats.set(profile.solve_threats);
+        self.solver_node_budget.set(profile.solver_node_budget);
+        self.solve_vct.set(profile.solve_vct);
+    }
+
+    // the same seed gives the same noise and blunders
+    pub fn set_seed(&self, seed: u64) {
+        self.random.set_seed(seed);
+    }
+
+    pub fn rule(&self) -> GameRule {
+        return self.rule_checker.rule();
+    }
+
+    pub fn max_depth(&self) -> usize {
+        return self.max_depth.get();
+    }
+
+    pub fn set_max_depth(&self, max_depth: usize) {
+        self.max_depth.set(max_depth);
+    }
+
+    pub fn time_budget(&self) -> Option<Duration> {
+        return self.time_budget.get();
+    }
+
+    // the deepening stops once the time is used up, None searches every depth
+    pub fn set_time_budget(&self, time_budget: Option<Duration>) {
+        self.time_budget.set(time_budget);
+    }
+
+    pub fn max_candidates(&self) -> usize {
+        return self.max_candidates.get();
+    }
+
+    pub fn set_max_candidates(&self, max_candidates: usize) {
+        self.max_candidates.set(max_candidates);
+    }
+
+    // the entries stored so far are dropped
+    pub fn set_table_size_mb(&self, size_mb: usize) {
+        *self.table.borrow_mut() = TranspositionTable::create_with_size_mb(size_mb);
+    }
+
+    // the probes and hits of the last search
+    pub fn table_stats(&self) -> TableStats {
+        return self.table.borrow().stats();
+    }
+
+    pub fn clear_table(&self) {
+        self.table.borrow().clear();
+    }
+
+    // iterative deepening negamax, the board is restored before returning
+    pub fn search(&self, chess: ChessType) -> SearchResult {
+        self.nodes.set(0);
+        self.table.borrow().new_search();
+        self.principal_variation.borrow_mut().clear();
+        self.aborted.set(false);
+        self.deadline.set(self.time_budget.get().map(|budget| Instant::now() + budget));
+
+        if self.solve_threats.get() {
+            if let Some(result) = self.solve_forced_win(chess) {
+                return result;
+            }
+        }
+
+        let mut result = SearchResult {
+            best_move: None,
+            score: 0,
+            principal_variation: Vec::new(),
+            depth: 0,
+            nodes: 0,
+        };
+
+        for depth in 1..(self.max_depth.get() + 1) {
+            let mut pv = Vec::new();
+            let score = self.negamax(chess, depth, 0, -INFINITE_SCORE, INFINITE_SCORE,
+                                     true, &mut pv);
+
+            // the scores of a depth cut short mean nothing, without a
+            // finished depth the best looking candidate is played
+            if self.aborted.get() {
+                if result.best_move.is_none() {
+                    result.best_move = self.generate_moves(chess, None).first().cloned();
+                    result.principal_variation = result.best_move.into_iter().collect();
+                }
+                result.nodes = self.nodes.get();
+                break;
+            }
+
+            *self.principal_variation.borrow_mut() = pv.clone();
+            result = SearchResult {
+                best_move: pv.first().cloned(),
+                score,
+                principal_variation: pv,
+                depth,
+                nodes: self.nodes.get(),
+            };
+
+            if result.is_win() || result.is_loss() {
+                break;
+            }
+        }
+
+        self.blunder(chess, &mut result);
+        return result;
+    }
+
+    // a five and the block of one are never given away
+    fn blunder(&self, chess: ChessType, result: &mut SearchResult) {
+        if !self.random.chance(self.blunder_rate.get()) {
+            return;
+        }
+
+        let moves = self.generate_moves(chess, None);
+        if moves.is_empty() || self.score_move(moves[0], chess) >= WIN_SCORE / 2 {
+            return;
+        }
+
+        let count = moves.len().min(BLUNDER_CANDIDATES);
+        let coord = moves[self.random.below(count as u64) as usize];
+        result.best_move = Some(coord);
+        result.principal_variation = vec![coord];
+    }
+
+    fn is_out_of_budget(&self) -> bool {
+        let nodes = self.nodes.get();
+        let out_of_nodes = self.node_budget.get().map_or(false, |budget| nodes > budget);
+        let out_of_time = nodes % CLOCK_CHECK_NODES == 0
+            && self.deadline.get().map_or(false, |deadline| Instant::now() >= deadline);
+        if out_of_nodes || out_of_time {
+            self.aborted.set(true);
+        }
+
+        return self.aborted.get();
+    }
+
+    // a win by continuous fours, or threes and fours, goes further than
+    // the search would look, a five at once is left to the search
+    fn solve_forced_win(&self, chess: ChessType) -> Option<SearchResult> {
+        // the search keeps at least half of the time
+        let solver = ThreatSolver::create_with_rule(&self.board, self.rule());
+        solver.set_node_budget(self.solver_node_budget.get());
+        solver.set_deadline(self.time_budget.get().map(|budget| Instant::now() + budget / 2));
+
+        let mut solution = solver.solve_vcf(chess);
+        if solution.status != SsWin && self.solve_vct.get() {
+            solution = solver.solve_vct(chess);
+        }
+        if solution.status != SsWin || solution.sequence.len() <= 1 {
+            return None;
+        }
+
+        *self.principal_variation.borrow_mut() = solution.sequence.clone();
+        return Some(SearchResult {
+            best_move: solution.sequence.first().cloned(),
+            score: WIN_SCORE - (solution.sequence.len() as i32 - 1),
+            depth: solution.sequence.len(),
+            principal_variation: solution.sequence,
+            nodes: solution.nodes,
+        });
+    }
+
+    fn negamax(&self, chess: ChessType, depth: usize, ply: i32, mut alpha: i32, beta: i32,
+               on_pv: bool, pv: &mut Vec<Coord>) -> i32 {
+        self.nodes.set(self.nodes.get() + 1);
+        if self.is_out_of_budget() {
+            return 0;
+        }
+
+        if depth == 0 || ply >= MAX_PLY {
+            return self.evaluate(chess) + self.random.spread(self.noise.get());
+        }
+
+        let hash = self.position_hash(chess);
+        let entry = self.table.borrow().probe(hash);
+        if let Some(entry) = entry {
+            if ply > 0 && entry.depth >= depth {
+                // an exact score inside the window is searched again, or
+                // the principal variation would end at this node
+                let score = score_from_table(entry.score, ply);
+                let cutoff = match entry.bound {
+                    BtExact => score <= alpha || score >= beta,
+                    BtLower => score >= beta,
+                    BtUpper => score <= alpha,
+                };
+                if cutoff {
+                    pv.extend(entry.best_move);
+                    return score;
+                }
+            }
+        }
+
+        let pv_move = match on_pv {
+            true => self.principal_variation.borrow().get(ply as usize).cloned(),
+            false => None,
+        };
+        let first_move = pv_move.or_else(|| entry.and_then(|entry| entry.best_move));
+        let moves = self.generate_moves(chess, first_move);
+        if moves.is_empty() {
+            return 0;
+        }
+
+        let alpha_before = alpha;
+        let mut best_score = -INFINITE_SCORE;
+        let mut best_move = moves[0];
+        for coord in moves.iter() {
+            let mut child_pv = Vec::new();
+
+            self.board.put_chess_at(*coord, chess).unwrap();
+            let score = match self.is_five(*coord, chess) {
+                true => WIN_SCORE - ply,
+                false => -self.negamax(chess.get_different_chess(), depth - 1, ply + 1,
+                                       -beta, -alpha, pv_move == Some(*coord), &mut child_pv),
+            };
+            self.board.remove_chess_at(*coord).unwrap();
+
+            if score > best_score {
+                best_score = score;
+                best_move = *coord;
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(*coord);
+                    pv.append(&mut child_pv);
+                }
+            }
+
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        if self.aborted.get() {
+            return best_score;
+        }
+
+        if pv.is_empty() {
+            pv.push(moves[0]);
+        }
+
+        let bound = match best_score {
+            score if score <= alpha_before => BtUpper,
+            score if score >= beta => BtLower,
+            _ => BtExact,
+        };
+        self.table.borrow().store(TableEntry::new(hash, depth, bound,
+                                                  score_to_table(best_score, ply), Some(best_move)));
+
+        return best_score;
+    }
+
+    fn position_hash(&self, chess: ChessType) -> u64 {
+        match chess {
+            CtBlack => return self.board.hash(),
+            CtWhite => return self.board.hash() ^ WHITE_TO_MOVE_KEY,
+        }
+    }
+
+    fn evaluate(&self, chess: ChessType) -> i32 {
+        let score = self.rule_checker.get_evaluation();
+        match chess {
+            CtBlack => return score,
+            CtWhite => return -score,
+        }
+    }
+
+    fn generate_moves(&self, chess: ChessType, pv_move: Option<Coord>) -> Vec<Coord> {
+        let size = self.board.size();
+        let mut near = vec![false; self.board.cp_count()];
+        let mut have_chess = false;
+
+        for row in 0..size {
+            for col in 0..size {
+                if !self.board.have_chess_at(Coord{row, col}) {
+                    continue;
+                }
+
+                have_chess = true;
+                let row_begin = row.saturating_sub(CANDIDATE_DISTANCE);
+                let col_begin = col.saturating_sub(CANDIDATE_DISTANCE);
+                let row_end = (row + CANDIDATE_DISTANCE + 1).min(size);
+                let col_end = (col + CANDIDATE_DISTANCE + 1).min(size);
+                for row_n in row_begin..row_end {
+                    for col_n in col_begin..col_end {
+                        near[row_n * size + col_n] = true;
+                    }
+                }
+            }
+        }
+
+        if !have_chess {
+            if size == 0 {
+                return Vec::new();
+            }
+            return vec![Coord{row: size / 2, col: size / 2}];
+        }
+
+        let mut scored_moves = Vec::new();
+        for row in 0..size {
+            for col in 0..size {
+                let coord = Coord{row, col};
+                if near[row * size + col] && self.is_legal(coord, chess) {
+                    scored_moves.push((self.score_move(coord, chess), coord));
+                }
+            }
+        }
+
+        scored_moves.sort_by(|a, b| b.0.cmp(&a.0));
+        scored_moves.truncate(self.max_candidates.get());
+
+        let mut moves: Vec<Coord> = scored_moves.iter().map(|m| m.1).collect();
+        if let Some(pv_coord) = pv_move {
+            if self.is_legal(pv_coord, chess) {
+                moves.retain(|coord| *coord != pv_coord);
+                moves.insert(0, pv_coord);
+            }
+        }
+
+        return moves;
+    }
+
+    fn is_legal(&self, coord: Coord, chess: ChessType) -> bool {
+        return !self.board.have_chess_at(coord)
+            && self.rule_checker.check_forbidden(coord, chess).is_none();
+    }
+
+    // winning at once goes first, then blocking the opponent's five,
+    // then the evaluation gained for both sides at this point
+    fn score_move(&self, coord: Coord, chess: ChessType) -> i32 {
+        let other = chess.get_different_chess();
+        let own_before = self.evaluate(chess);
+        let other_before = self.evaluate(other);
+
+        self.board.put_chess_at(coord, chess).unwrap();
+        let own_five = self.is_five(coord, chess);
+        let own_gain = self.evaluate(chess) - own_before;
+        self.board.remove_chess_at(coord).unwrap();
+
+        self.board.put_chess_at(coord, other).unwrap();
+        let other_five = self.is_five(coord, other);
+        let other_gain = self.evaluate(other) - other_before;
+        self.board.remove_chess_at(coord).unwrap();
+
+        if own_five {
+            return WIN_SCORE;
+        } else if other_five {
+            return WIN_SCORE / 2;
+        }
+
+        return own_gain.saturating_add(other_gain);
+    }
+
+    // the rule checker follows every move and wins by its rule, a five is
+    // there when the game is over through a line holding the chess just put
+    fn is_five(&self, coord: Coord, chess: ChessType) -> bool {
+        if self.rule_checker.game_status() != GsGameOver(chess) {
+            return false;
+        }
+
+        match self.rule_checker.winning_line() {
+            Some(line) => return line.contains(&coord),
+            None => return false,
+        }
+    }
+}
+
+// a win is stored as the plies from the node on rather than from the
+// root, so that the same entry fits wherever the position is reached
+fn score_to_table(score: i32, ply: i32) -> i32 {
+    if score >= WIN_SCORE - MAX_PLY {
+        return score + ply;
+    } else if score <= -WIN_SCORE + MAX_PLY {
+        return score - ply;
+    }
+
+    return score;
+}
+
+fn score_from_table(score: i32, ply: i32) -> i32 {
+    if score >= WIN_SCORE - MAX_PLY {
+        return score - ply;
+    } else if score <= -WIN_SCORE + MAX_PLY {
+        return score + ply;
+    }
+
+    return score;
+}
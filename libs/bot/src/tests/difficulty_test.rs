@@ -0,0 +1,123 @@
+use backend::board::*;
+
+use backend::cross_point::ChessType::*;
+
+use super::super::difficulty::*;
+use super::super::search::*;
+
+use super::super::difficulty::Difficulty::*;
+
+#[test]
+fn profiles() {
+    let easy = DfEasy.profile();
+    let normal = DfNormal.profile();
+    let hard = DfHard.profile();
+
+    assert!(easy.max_depth < normal.max_depth && normal.max_depth < hard.max_depth);
+    assert!(easy.noise > normal.noise && normal.noise > hard.noise);
+    assert!(easy.blunder_rate > normal.blunder_rate && normal.blunder_rate > hard.blunder_rate);
+    assert_eq!(easy.solve_threats, false);
+    assert!(normal.solver_node_budget < hard.solver_node_budget);
+    assert_eq!(normal.solve_vct, false);
+
+    // hard is the bot as it searches without a profile
+    assert_eq!(hard.node_budget, None);
+    assert_eq!(hard.time_budget, None);
+    assert_eq!(hard.noise, 0);
+    assert_eq!(hard.blunder_rate, 0.0);
+    assert_eq!(hard.solve_threats, true);
+    assert_eq!(hard.solve_vct, true);
+}
+
+#[test]
+fn random() {
+    let random = Random::create_with_seed(7);
+    let first: Vec<u64> = (0..4).map(|_| random.next()).collect();
+    random.set_seed(7);
+    let second: Vec<u64> = (0..4).map(|_| random.next()).collect();
+    assert_eq!(first, second);
+
+    for _ in 0..100 {
+        let spread = random.spread(10);
+        assert!(spread >= -10 && spread <= 10);
+        assert!(random.below(3) < 3);
+    }
+    assert_eq!(random.spread(0), 0);
+    assert_eq!(random.chance(0.0), false);
+    assert_eq!(random.chance(1.0), true);
+}
+
+#[test]
+fn node_budget() {
+    let board = Board::new();
+    let bot = Bot::new(board.clone());
+    bot.set_difficulty(DfEasy);
+    bot.set_seed(1);
+
+    for &(row, col, chess) in [(7, 7, CtBlack), (7, 8, CtWhite), (8, 8, CtBlack),
+                               (6, 6, CtWhite), (8, 7, CtBlack), (8, 6, CtWhite)].iter() {
+        board.put_chess_at(Coord{row, col}, chess).unwrap();
+    }
+
+    let result = bot.search(CtBlack);
+    assert!(result.best_move.is_some());
+    assert!(result.nodes <= DfEasy.profile().node_budget.unwrap() + 1);
+    assert_eq!(board.have_chess_at(result.best_move.unwrap()), false);
+}
+
+// the weakening never gives away a five or the block of one
+#[test]
+fn easy_keeps_fives() {
+    let board = Board::new();
+    let bot = Bot::new(board.clone());
+    bot.set_difficulty(DfEasy);
+
+    for col in 3..7 {                                                   // _ X X X X _
+        board.put_chess_at(Coord{row: 7, col}, CtBlack).unwrap();                // O O O _ _ _
+    }
+    for col in 3..6 {
+        board.put_chess_at(Coord{row: 8, col}, CtWhite).unwrap();
+    }
+
+    for seed in 0..20 {
+        bot.set_seed(seed);
+        let best_move = bot.search(CtBlack).best_move;
+        assert!(best_move == Some(Coord{row: 7, col: 2}) || best_move == Some(Coord{row: 7, col: 7}));
+    }
+
+    let board = Board::new();
+    let bot = Bot::new(board.clone());
+    bot.set_difficulty(DfEasy);
+
+    board.put_chess_at(Coord{row: 7, col: 2}, CtBlack).unwrap();                 // X O O O O _
+    for col in 3..7 {                                                   // _ X X _ _ _
+        board.put_chess_at(Coord{row: 7, col}, CtWhite).unwrap();
+    }
+    board.put_chess_at(Coord{row: 8, col: 3}, CtBlack).unwrap();
+    board.put_chess_at(Coord{row: 8, col: 4}, CtBlack).unwrap();
+
+    for seed in 0..20 {
+        bot.set_seed(seed);
+        assert_eq!(bot.search(CtBlack).best_move, Some(Coord{row: 7, col: 7}));
+    }
+}
+
+#[test]
+fn easy_blunders() {
+    let board = Board::new();
+    let bot = Bot::new(board.clone());
+    bot.set_difficulty(DfEasy);
+
+    board.put_chess_at(Coord{row: 7, col: 7}, CtBlack).unwrap();
+    board.put_chess_at(Coord{row: 7, col: 8}, CtWhite).unwrap();
+
+    let mut moves = Vec::new();
+    for seed in 0..20 {
+        bot.set_seed(seed);
+        let best_move = bot.search(CtBlack).best_move.unwrap();
+        if !moves.contains(&best_move) {
+            moves.push(best_move);
+        }
+    }
+    assert!(moves.len() > 1);
+}
@@ -0,0 +1,5 @@
+mod search_test;
+mod transposition_test;
+mod threat_test;
+mod difficulty_test;
+mod players_test;
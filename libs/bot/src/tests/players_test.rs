@@ -0,0 +1,82 @@
+use std::rc::Rc;
+
+use backend::board::*;
+use backend::game_session::*;
+use backend::utils::*;
+
+use backend::cross_point::ChessType::*;
+use backend::opening::OpeningRule::*;
+
+use super::super::players::*;
+
+use super::super::players::PlayerKind::*;
+
+fn coord(row: usize, col: usize) -> Coord {
+    return Coord{row, col};
+}
+
+fn create_players(black: PlayerKind, white: PlayerKind) -> Players {
+    let players = Players::create_with_session(Rc::new(GameSession::new()), black, white);
+    players.bot().set_max_depth(1);
+    return players;
+}
+
+#[test]
+fn human_against_bot() {
+    let players = create_players(PkHuman, PkBot);
+    assert_eq!(players.is_bot_to_move(), false);
+    assert_eq!(players.play_bot_move(), Ok(None));
+
+    players.put_human_chess(coord(7, 7)).unwrap();
+    assert_eq!(players.is_bot_to_move(), true);
+    assert_eq!(players.put_human_chess(coord(8, 8)).unwrap_err().kind, ErrorKind::WrongTurn);
+
+    let step = players.play_bot_move().unwrap().unwrap();
+    assert_eq!(step.chess, CtWhite);
+    assert_eq!(players.session().last_step(), Some(step));
+    assert_eq!(players.is_bot_to_move(), false);
+
+    // the reply of the bot goes with the own move
+    players.take_back_human_move().unwrap();
+    assert_eq!(players.session().steps().len(), 0);
+}
+
+#[test]
+fn bot_plays_black() {
+    let players = create_players(PkBot, PkHuman);
+    assert_eq!(players.put_human_chess(coord(7, 7)).unwrap_err().kind, ErrorKind::WrongTurn);
+    assert_eq!(players.play_bot_move().unwrap().map(|step| step.coord), Some(coord(7, 7)));
+    players.put_human_chess(coord(8, 8)).unwrap();
+
+    players.take_back_human_move().unwrap();
+    assert_eq!(players.session().steps().len(), 1);
+    assert_eq!(players.player_of(players.session().next_chess()), PkHuman);
+}
+
+#[test]
+fn two_humans() {
+    let players = create_players(PkHuman, PkHuman);
+    players.put_human_chess(coord(7, 7)).unwrap();
+    players.put_human_chess(coord(8, 8)).unwrap();
+    assert_eq!(players.play_bot_move(), Ok(None));
+
+    players.take_back_human_move().unwrap();
+    assert_eq!(players.session().steps().len(), 1);
+
+    players.set_players(PkHuman, PkBot);
+    assert_eq!(players.is_bot_to_move(), true);
+}
+
+// the humans play the opening and the setup, the bot waits for the game
+#[test]
+fn opening_and_setup() {
+    let players = create_players(PkBot, PkBot);
+    players.session().set_opening_rule(OrSwap).unwrap();
+    assert_eq!(players.is_bot_to_move(), false);
+
+    players.session().set_opening_rule(OrFree).unwrap();
+    players.session().begin_setup().unwrap();
+    assert_eq!(players.is_bot_to_move(), false);
+    players.session().finish_setup(CtBlack).unwrap();
+    assert_eq!(players.is_bot_to_move(), true);
+}
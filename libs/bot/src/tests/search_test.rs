@@ -0,0 +1,125 @@
+use backend::board::*;
+use backend::rule_checker::*;
+
+use backend::cross_point::ChessType::*;
+use backend::rule_checker::GameRule::*;
+
+use super::super::search::*;
+
+use super::super::difficulty::Difficulty::*;
+
+#[test]
+fn empty_board() {
+    let board = Board::new();
+    let bot = Bot::new(board.clone());
+
+    let result = bot.search(CtBlack);
+    assert_eq!(result.best_move, Some(Coord{row: 7, col: 7}));
+    assert_eq!(board.have_chess_at(Coord{row: 7, col: 7}), false);
+}
+
+#[test]
+fn win_at_once() {
+    let board = Board::new();
+    let bot = Bot::new(board.clone());
+
+    for col in 3..7 {                                                   // _ X X X X _
+        board.put_chess_at(Coord{row: 7, col}, CtBlack).unwrap();                // O O O _ _ _
+    }
+    for col in 3..6 {
+        board.put_chess_at(Coord{row: 8, col}, CtWhite).unwrap();
+    }
+
+    let result = bot.search(CtBlack);
+    assert!(result.is_win());
+    assert_eq!(result.depth, 1);
+    assert!(result.best_move == Some(Coord{row: 7, col: 2}) ||
+        result.best_move == Some(Coord{row: 7, col: 7}));
+    assert_eq!(result.principal_variation.len(), 1);
+}
+
+#[test]
+fn block_four() {
+    let board = Board::new();
+    let bot = Bot::new(board.clone());
+
+    board.put_chess_at(Coord{row: 7, col: 2}, CtBlack).unwrap();                 // X O O O O _
+    for col in 3..7 {                                                   // _ X X _ _ _
+        board.put_chess_at(Coord{row: 7, col}, CtWhite).unwrap();
+    }
+    board.put_chess_at(Coord{row: 8, col: 3}, CtBlack).unwrap();
+    board.put_chess_at(Coord{row: 8, col: 4}, CtBlack).unwrap();
+
+    let result = bot.search(CtBlack);
+    assert_eq!(result.best_move, Some(Coord{row: 7, col: 7}));
+    assert_eq!(result.is_loss(), false);
+}
+
+#[test]
+fn lost_position() {
+    let board = Board::new();
+    let bot = Bot::new(board.clone());
+
+    for col in 4..8 {                                                   // _ O O O O _
+        board.put_chess_at(Coord{row: 7, col}, CtWhite).unwrap();                // _ X X X _ _
+    }
+    for col in 4..7 {
+        board.put_chess_at(Coord{row: 8, col}, CtBlack).unwrap();
+    }
+
+    let result = bot.search(CtBlack);
+    assert!(result.is_loss());
+    assert_eq!(result.depth, 2);
+
+    let result = bot.search(CtWhite);
+    assert!(result.is_win());
+    assert_eq!(result.principal_variation.len(), 1);
+}
+
+#[test]
+fn board_restored() {
+    let board = Board::new();
+    let bot = Bot::create_with_depth(board.clone(), 3);
+
+    board.put_chess_at(Coord{row: 7, col: 7}, CtBlack).unwrap();
+    board.put_chess_at(Coord{row: 7, col: 8}, CtWhite).unwrap();
+    board.put_chess_at(Coord{row: 8, col: 8}, CtBlack).unwrap();
+
+    let result = bot.search(CtWhite);
+    assert_eq!(result.depth, 3);
+    assert_eq!(result.principal_variation.len(), 3);
+    assert!(result.nodes > 0);
+
+    let mut count = 0;
+    for row in 0..15 {
+        for col in 0..15 {
+            if board.have_chess_at(Coord{row, col}) {
+                count += 1;
+            }
+        }
+    }
+    assert_eq!(count, 3);
+}
+
+// black under renju leaves out the double three at the crossing
+#[test]
+fn renju_forbidden() {
+    let board = Board::new();
+    for &(row, col) in [(7, 5), (7, 6), (5, 7), (6, 7)].iter() {
+        board.put_chess_at(Coord{row, col}, CtBlack).unwrap();
+    }
+    for &(row, col) in [(9, 9), (9, 4), (4, 9), (10, 10)].iter() {
+        board.put_chess_at(Coord{row, col}, CtWhite).unwrap();
+    }
+
+    let bot = Bot::create_with_depth(board.clone(), 2);
+    assert_eq!(bot.search(CtBlack).best_move, Some(Coord{row: 7, col: 7}));
+
+    // the threat solver finds no win for black and would look for long
+    let bot = Bot::create_with_rule(board.clone(), GrRenju);
+    bot.set_difficulty(DfNormal);
+    bot.set_seed(1);
+    let best_move = bot.search(CtBlack).best_move.unwrap();
+    assert_ne!(best_move, Coord{row: 7, col: 7});
+    assert_eq!(RuleChecker::create_with_rule(board.clone(), GrRenju).check_forbidden(best_move, CtBlack), None);
+}
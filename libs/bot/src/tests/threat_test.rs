@@ -0,0 +1,174 @@
+use std::time::Instant;
+
+use backend::board::*;
+use backend::cross_point::*;
+use backend::rule_checker::*;
+
+use backend::cross_point::ChessType::*;
+use backend::rule_checker::GameRule::*;
+use backend::rule_checker::GameStatus::*;
+
+use super::super::search::*;
+use super::super::threat::*;
+use super::super::threat::SolveStatus::*;
+
+fn put_all(board: &Board, coords: &[(usize, usize)], chess: ChessType) {
+    for &(row, col) in coords.iter() {
+        board.put_chess_at(Coord{row, col}, chess).unwrap();
+    }
+}
+
+// plays the sequence on the board, the two sides taking turns, and
+// checks that the last move of the attacker makes five
+fn assert_wins(board: &std::rc::Rc<Board>, sequence: &[Coord], chess: ChessType) {
+    let rule_checker = RuleChecker::create_with_detail(board.clone());
+    assert_eq!(sequence.len() % 2, 1);
+
+    let mut side = chess;
+    for (index, coord) in sequence.iter().enumerate() {
+        assert_eq!(rule_checker.game_status(), GsGameContinue, "move {}", index);
+        board.put_chess_at(*coord, side).unwrap();
+        side = side.get_different_chess();
+    }
+    assert_eq!(rule_checker.game_status(), GsGameOver(chess));
+}
+
+#[test]
+fn four_four() {
+    let board = Board::new();
+    put_all(&board, &[(7, 4), (7, 5), (7, 6), (3, 8), (4, 8), (5, 8)], CtBlack);   // O X X X _ _
+    put_all(&board, &[(7, 3), (2, 8)], CtWhite);
+
+    let solver = ThreatSolver::create_with_board(&board);
+    let solution = solver.solve_vcf(CtBlack);
+    assert_eq!(solution.status, SsWin);
+    assert!(solution.nodes > 0);
+    assert_wins(&board, &solution.sequence, CtBlack);
+}
+
+#[test]
+fn several_fours() {
+    let board = Board::new();
+    // the four on row 7 has to come first, its chess is part of the
+    // four-four on column 8 and row 6
+    put_all(&board, &[(7, 4), (7, 5), (7, 6), (4, 8), (5, 8), (6, 5), (6, 6), (6, 7)], CtBlack);
+    put_all(&board, &[(7, 3), (3, 8), (6, 4), (6, 9), (9, 8)], CtWhite);
+
+    let solver = ThreatSolver::create_with_board(&board);
+    let solution = solver.solve_vcf(CtBlack);
+    assert_eq!(solution.status, SsWin);
+    assert!(solution.sequence.len() >= 5);
+    assert_wins(&board, &solution.sequence, CtBlack);
+}
+
+#[test]
+fn no_win() {
+    let board = Board::new();
+    put_all(&board, &[(7, 4), (7, 5), (7, 6)], CtBlack);                    // O X X X _ _
+    put_all(&board, &[(7, 3), (8, 8)], CtWhite);
+
+    let solver = ThreatSolver::create_with_board(&board);
+    assert_eq!(solver.solve_vcf(CtBlack).status, SsNoWin);
+    assert_eq!(solver.solve_vcf(CtWhite).status, SsNoWin);
+    assert!(solver.solve_vcf(CtBlack).sequence.is_empty());
+}
+
+#[test]
+fn block_five_first() {
+    let board = Board::new();
+    put_all(&board, &[(7, 4), (7, 5), (7, 6), (3, 8), (4, 8), (5, 8), (12, 1)], CtBlack);
+    put_all(&board, &[(7, 3), (2, 8), (12, 2), (12, 3), (12, 4), (12, 5)], CtWhite);
+
+    // black has to block at (12, 6), which makes no four
+    let solver = ThreatSolver::create_with_board(&board);
+    assert_eq!(solver.solve_vcf(CtBlack).status, SsNoWin);
+
+    let solution = solver.solve_vcf(CtWhite);
+    assert_eq!(solution.status, SsWin);
+    assert_eq!(solution.sequence, vec![Coord{row: 12, col: 6}]);
+}
+
+#[test]
+fn three_three() {
+    let board = Board::new();
+    put_all(&board, &[(7, 5), (7, 6), (5, 7), (6, 7)], CtBlack);
+    put_all(&board, &[(10, 10)], CtWhite);
+
+    let solver = ThreatSolver::create_with_board(&board);
+    assert_eq!(solver.solve_vcf(CtBlack).status, SsNoWin);
+
+    let solution = solver.solve_vct(CtBlack);
+    assert_eq!(solution.status, SsWin);
+    assert_wins(&board, &solution.sequence, CtBlack);
+}
+
+#[test]
+fn node_budget() {
+    let board = Board::new();
+    put_all(&board, &[(7, 5), (7, 6), (5, 7), (6, 7)], CtBlack);
+    put_all(&board, &[(10, 10)], CtWhite);
+
+    let solver = ThreatSolver::create_with_board(&board);
+    solver.set_node_budget(1);
+    let solution = solver.solve_vct(CtBlack);
+    assert_eq!(solution.status, SsUnknown);
+    assert!(solution.sequence.is_empty());
+}
+
+#[test]
+fn deadline() {
+    let board = Board::new();
+    put_all(&board, &[(7, 5), (7, 6), (5, 7), (6, 7)], CtBlack);
+    put_all(&board, &[(10, 10)], CtWhite);
+
+    let solver = ThreatSolver::create_with_board(&board);
+    solver.set_deadline(Some(Instant::now()));
+    assert_eq!(solver.solve_vct(CtBlack).status, SsUnknown);
+
+    solver.set_deadline(None);
+    assert_eq!(solver.solve_vct(CtBlack).status, SsWin);
+}
+
+#[test]
+fn bot_plays_fours() {
+    let board = Board::new();
+    put_all(&board, &[(7, 4), (7, 5), (7, 6), (3, 8), (4, 8), (5, 8)], CtBlack);
+    put_all(&board, &[(7, 3), (2, 8)], CtWhite);
+
+    let bot = Bot::new(board.clone());
+    let result = bot.search(CtBlack);
+    assert!(result.is_win());
+    assert!(result.principal_variation.len() >= 3);
+    assert_eq!(result.best_move, result.principal_variation.first().cloned());
+    assert_eq!(board.have_chess_at(result.best_move.unwrap()), false);
+}
+
+// six in a row is no five under the standard rule
+#[test]
+fn standard_overline() {
+    let board = Board::new();
+    put_all(&board, &[(7, 2), (7, 3), (7, 4), (7, 5), (7, 7)], CtBlack);      // O X X X X _ X
+    put_all(&board, &[(7, 1), (9, 9), (10, 10), (11, 12), (3, 12)], CtWhite);
+
+    let solution = ThreatSolver::create_with_board(&board).solve_vcf(CtBlack);
+    assert_eq!(solution.status, SsWin);
+    assert_eq!(solution.sequence, vec![Coord{row: 7, col: 6}]);
+
+    let solution = ThreatSolver::create_with_rule(&board, GrStandard).solve_vcf(CtBlack);
+    assert_ne!(solution.sequence.first(), Some(&Coord{row: 7, col: 6}));
+}
+
+// the four four of black is forbidden under renju
+#[test]
+fn renju_four_four() {
+    let board = Board::new();
+    put_all(&board, &[(7, 4), (7, 5), (7, 6), (4, 7), (5, 7), (6, 7)], CtBlack);
+    put_all(&board, &[(7, 3), (3, 7), (7, 9), (9, 7), (12, 12), (12, 2)], CtWhite);
+
+    let solution = ThreatSolver::create_with_board(&board).solve_vcf(CtBlack);
+    assert_eq!(solution.status, SsWin);
+    assert_eq!(solution.sequence.first(), Some(&Coord{row: 7, col: 7}));
+
+    let solution = ThreatSolver::create_with_rule(&board, GrRenju).solve_vcf(CtBlack);
+    assert_eq!(solution.status, SsNoWin);
+}
@@ -0,0 +1,96 @@
+use backend::board::*;
+
+use backend::cross_point::ChessType::*;
+
+use super::super::search::*;
+use super::super::transposition::*;
+use super::super::transposition::BoundType::*;
+
+#[test]
+fn probe_and_store() {
+    let table = TranspositionTable::create_with_size_mb(1);
+    assert!(table.capacity() > 1);
+    assert_eq!(table.probe(42), None);
+
+    let entry = TableEntry::new(42, 3, BtExact, 100, Some(Coord{row: 7, col: 7}));
+    table.store(entry);
+    let found = table.probe(42).unwrap();
+    assert_eq!(found.depth, 3);
+    assert_eq!(found.bound, BtExact);
+    assert_eq!(found.score, 100);
+    assert_eq!(found.best_move, Some(Coord{row: 7, col: 7}));
+
+    let stats = table.stats();
+    assert_eq!((stats.probes, stats.hits, stats.stores), (2, 1, 1));
+    assert_eq!(stats.hit_rate(), 0.5);
+
+    table.clear();
+    assert_eq!(table.probe(42), None);
+}
+
+#[test]
+fn replacement() {
+    // one slot, so every position goes to the same place
+    let table = TranspositionTable::create_with_size_mb(0);
+    assert_eq!(table.capacity(), 1);
+
+    table.store(TableEntry::new(1, 4, BtLower, 10, None));
+    table.store(TableEntry::new(2, 2, BtExact, 20, None));
+    assert!(table.probe(1).is_some());
+    assert_eq!(table.probe(2), None);
+
+    // the same position is always replaced
+    table.store(TableEntry::new(1, 1, BtUpper, 5, None));
+    assert_eq!(table.probe(1).unwrap().depth, 1);
+
+    table.store(TableEntry::new(2, 1, BtExact, 20, None));
+    assert!(table.probe(2).is_some());
+
+    // an entry from an older search gives way
+    table.store(TableEntry::new(3, 5, BtExact, 30, None));
+    table.new_search();
+    table.store(TableEntry::new(4, 1, BtExact, 40, None));
+    assert!(table.probe(4).is_some());
+    assert_eq!(table.probe(3), None);
+}
+
+#[test]
+fn search_with_table() {
+    let board = Board::new();
+    let bot = Bot::create_with_depth(board.clone(), 3);
+
+    board.put_chess_at(Coord{row: 7, col: 7}, CtBlack).unwrap();
+    board.put_chess_at(Coord{row: 7, col: 8}, CtWhite).unwrap();
+    board.put_chess_at(Coord{row: 8, col: 8}, CtBlack).unwrap();
+
+    let first = bot.search(CtWhite);
+    assert!(bot.table_stats().stores > 0);
+    assert!(bot.table_stats().hits > 0);
+
+    // the second search starts with the entries of the first one
+    let second = bot.search(CtWhite);
+    assert_eq!(second.best_move, first.best_move);
+    assert_eq!(second.score, first.score);
+    assert_eq!(second.principal_variation.len(), 3);
+    assert!(second.nodes <= first.nodes);
+    assert!(bot.table_stats().hit_rate() > 0.0);
+}
+
+#[test]
+fn search_without_table() {
+    let board = Board::new();
+    let bot = Bot::create_with_depth(board.clone(), 3);
+    let bot_small = Bot::create_with_depth(board.clone(), 3);
+    bot_small.set_table_size_mb(0);
+
+    for col in 4..8 {                                                   // _ O O O O _
+        board.put_chess_at(Coord{row: 7, col}, CtWhite).unwrap();                // _ X X X _ _
+    }
+    for col in 4..7 {
+        board.put_chess_at(Coord{row: 8, col}, CtBlack).unwrap();
+    }
+
+    assert!(bot.search(CtBlack).is_loss());
+    assert!(bot_small.search(CtBlack).is_loss());
+    assert!(bot_small.search(CtWhite).is_win());
+}
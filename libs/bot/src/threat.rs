@@ -0,0 +1,505 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::Instant;
+
+use backend::board::*;
+use backend::cross_point::*;
+use backend::forbidden_checker::*;
+use backend::rule_checker::*;
+use backend::zobrist::*;
+
+use backend::cross_point::ChessType::*;
+use backend::cross_point::CrossPointType::*;
+use backend::rule_checker::GameRule::*;
+use threat::SolveStatus::*;
+use threat::ThreatKind::*;
+
+const DEFAULT_NODE_BUDGET: u64 = 100_000;
+// the count of threats the attacker may make in a row
+const DEFAULT_MAX_DEPTH: usize = 20;
+// a three leaves the defender many answers, so there are few of them
+const DEFAULT_MAX_THREES: usize = 3;
+
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum ThreatKind {
+    // victory by continuous fours
+    TkVcf,
+    // victory by continuous threes and fours
+    TkVct,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum SolveStatus {
+    SsWin,
+    SsNoWin,
+    // the node budget or the time ran out before the search was through
+    SsUnknown,
+}
+
+// the sequence starts with the attacker and the two sides take turns up
+// to the five, after a three the defender may answer in several ways and
+// the line only follows the first of them
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Solution {
+    pub status: SolveStatus,
+    pub sequence: Vec<Coord>,
+    pub nodes: u64,
+}
+
+// proves or refutes a forced win of one side on a copy of the board, the
+// fives are the ones of the rule and black under renju makes no forbidden
+// attack, an answer of the defender is assumed to be allowed
+pub struct ThreatSolver {
+    size: usize,
+    rule: GameRule,
+    cells: RefCell<Vec<Option<ChessType>>>,
+    zobrist: ZobristTable,
+    hash: Cell<u64>,
+    node_budget: Cell<u64>,
+    deadline: Cell<Option<Instant>>,
+    max_depth: Cell<usize>,
+    max_threes: Cell<usize>,
+    nodes: Cell<u64>,
+    exhausted: Cell<bool>,
+    // the hash of a position the attacker does not win from with the
+    // threes left, and the most threats it was searched with
+    failed: RefCell<HashMap<(u64, usize), usize>>,
+}
+
+impl ThreatSolver {
+    pub fn create_with_board(board: &Board) -> ThreatSolver {
+        return ThreatSolver::create_with_rule(board, GrFreestyle);
+    }
+
+    pub fn create_with_rule(board: &Board, rule: GameRule) -> ThreatSolver {
+        let size = board.size();
+        let mut cells = vec![None; size * size];
+        for row in 0..size {
+            for col in 0..size {
+                cells[row * size + col] = board.get_chess_at(Coord{row, col}).ok();
+            }
+        }
+
+        return ThreatSolver {
+            size,
+            rule,
+            cells: RefCell::new(cells),
+            zobrist: ZobristTable::create_with_size(size),
+            hash: Cell::new(board.hash()),
+            node_budget: Cell::new(DEFAULT_NODE_BUDGET),
+            deadline: Cell::new(None),
+            max_depth: Cell::new(DEFAULT_MAX_DEPTH),
+            max_threes: Cell::new(DEFAULT_MAX_THREES),
+            nodes: Cell::new(0),
+            exhausted: Cell::new(false),
+            failed: RefCell::new(HashMap::new()),
+        };
+    }
+
+    pub fn rule(&self) -> GameRule {
+        return self.rule;
+    }
+
+    pub fn node_budget(&self) -> u64 {
+        return self.node_budget.get();
+    }
+
+    pub fn set_node_budget(&self, node_budget: u64) {
+        self.node_budget.set(node_budget);
+    }
+
+    // the search gives up like with the node budget once the time is past
+    pub fn set_deadline(&self, deadline: Option<Instant>) {
+        self.deadline.set(deadline);
+    }
+
+    pub fn max_depth(&self) -> usize {
+        return self.max_depth.get();
+    }
+
+    pub fn set_max_depth(&self, max_depth: usize) {
+        self.max_depth.set(max_depth);
+    }
+
+    pub fn max_threes(&self) -> usize {
+        return self.max_threes.get();
+    }
+
+    pub fn set_max_threes(&self, max_threes: usize) {
+        self.max_threes.set(max_threes);
+    }
+
+    pub fn solve_vcf(&self, chess: ChessType) -> Solution {
+        return self.solve(chess, TkVcf);
+    }
+
+    pub fn solve_vct(&self, chess: ChessType) -> Solution {
+        return self.solve(chess, TkVct);
+    }
+
+    // chess is the side to move and the one which attacks
+    pub fn solve(&self, chess: ChessType, kind: ThreatKind) -> Solution {
+        self.nodes.set(0);
+        self.exhausted.set(false);
+        self.failed.borrow_mut().clear();
+
+        // the wins with fewer threes are looked for first, they are found
+        // with far fewer nodes
+        let max_threes = match kind {
+            TkVcf => 0,
+            TkVct => self.max_threes.get(),
+        };
+        let mut sequence = None;
+        for threes in 0..(max_threes + 1) {
+            sequence = self.attack(chess, self.max_depth.get(), threes);
+            if sequence.is_some() || self.exhausted.get() {
+                break;
+            }
+        }
+        let status = match (sequence.is_some(), self.exhausted.get()) {
+            (true, _) => SsWin,
+            (false, true) => SsUnknown,
+            (false, false) => SsNoWin,
+        };
+
+        return Solution { status, sequence: sequence.unwrap_or_default(), nodes: self.nodes.get() };
+    }
+
+    fn attack(&self, chess: ChessType, depth: usize, threes: usize) -> Option<Vec<Coord>> {
+        self.nodes.set(self.nodes.get() + 1);
+        let out_of_time = self.deadline.get().map_or(false, |deadline| Instant::now() >= deadline);
+        if self.nodes.get() > self.node_budget.get() || out_of_time {
+            self.exhausted.set(true);
+            return None;
+        }
+
+        if let Some(coord) = self.five_points(chess).first() {
+            return Some(vec![*coord]);
+        }
+        let key = (self.hash.get(), threes);
+        if depth == 0 || self.failed.borrow().get(&key).map_or(false, |d| *d >= depth) {
+            return None;
+        }
+
+        // a five of the defender has to be blocked, and with a threat
+        let other = chess.get_different_chess();
+        let other_fives = self.five_points(other);
+        let candidates = match other_fives.len() {
+            0 => self.points_near(chess),
+            1 => other_fives,
+            _ => return None,
+        };
+
+        let mut three_moves = Vec::new();
+        for coord in candidates.iter() {
+            self.put(*coord, chess);
+            let fives = self.five_points_through(*coord, chess);
+            let is_three = fives.is_empty() && threes > 0 && self.is_three(*coord, chess);
+            self.remove(*coord);
+
+            // only the threats are checked for being forbidden, it is slow
+            if (fives.is_empty() && !is_three) || self.is_forbidden(*coord, chess) {
+                continue;
+            } else if is_three {
+                three_moves.push(*coord);
+                continue;
+            }
+
+            self.put(*coord, chess);
+            let result = match fives.len() {
+                1 => self.defend_four(chess, depth, threes, fives[0]),
+                _ => Some(vec![fives[0], fives[1]]),
+            };
+            self.remove(*coord);
+
+            if let Some(mut sequence) = result {
+                sequence.insert(0, *coord);
+                return Some(sequence);
+            }
+            if self.exhausted.get() {
+                return None;
+            }
+        }
+
+        // the fours are tried first, they leave the defender one answer
+        for coord in three_moves.iter() {
+            self.put(*coord, chess);
+            let result = self.defend_three(chess, depth, threes);
+            self.remove(*coord);
+
+            if let Some(mut sequence) = result {
+                sequence.insert(0, *coord);
+                return Some(sequence);
+            }
+            if self.exhausted.get() {
+                return None;
+            }
+        }
+
+        if !self.exhausted.get() {
+            self.failed.borrow_mut().insert(key, depth);
+        }
+        return None;
+    }
+
+    // the defender has to block the five, unless it has a five of its own
+    fn defend_four(&self, chess: ChessType, depth: usize, threes: usize,
+                   block: Coord) -> Option<Vec<Coord>> {
+        let other = chess.get_different_chess();
+        if !self.five_points(other).is_empty() {
+            return None;
+        }
+
+        self.put(block, other);
+        let result = self.attack(chess, depth - 1, threes);
+        self.remove(block);
+
+        let mut sequence = result?;
+        sequence.insert(0, block);
+        return Some(sequence);
+    }
+
+    // every answer which may stop the three has to lose
+    fn defend_three(&self, chess: ChessType, depth: usize, threes: usize) -> Option<Vec<Coord>> {
+        let mut line = None;
+        for coord in self.defence_points(chess).iter() {
+            self.put(*coord, chess.get_different_chess());
+            let result = self.attack(chess, depth - 1, threes - 1);
+            self.remove(*coord);
+
+            let mut sequence = result?;
+            if line.is_none() {
+                sequence.insert(0, *coord);
+                line = Some(sequence);
+            }
+        }
+
+        return line;
+    }
+
+    // an answer elsewhere leaves the open four to the attacker, so only the
+    // cross points of the lines of five it may still fill and the fours of
+    // the defender are worth trying
+    fn defence_points(&self, chess: ChessType) -> Vec<Coord> {
+        let other = chess.get_different_chess();
+        let mut is_defence = vec![false; self.size * self.size];
+
+        for row in 0..self.size {
+            for col in 0..self.size {
+                for &direction in DIRECTIONS.iter() {
+                    let window = match self.window(Coord{row, col}, direction) {
+                        Some(window) => window,
+                        None => continue,
+                    };
+
+                    let chesses: Vec<Option<ChessType>> =
+                        window.iter().map(|coord| self.chess_at(*coord)).collect();
+                    let own_count = chesses.iter().filter(|c| **c == Some(chess)).count();
+                    if own_count >= 3 && !chesses.contains(&Some(other)) {
+                        for coord in window.iter() {
+                            is_defence[coord.row * self.size + coord.col] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut points = Vec::new();
+        for coord in self.empty_points() {
+            let is_four = {
+                self.put(coord, other);
+                let is_four = !self.five_points_through(coord, other).is_empty();
+                self.remove(coord);
+                is_four
+            };
+            if is_defence[coord.row * self.size + coord.col] || is_four {
+                points.push(coord);
+            }
+        }
+
+        return points;
+    }
+
+    // a three can be made an open four, a four with two cross points to
+    // finish it on the same line
+    fn is_three(&self, coord: Coord, chess: ChessType) -> bool {
+        for &direction in DIRECTIONS.iter() {
+            for point in self.points_on_line(coord, direction) {
+                self.put(point, chess);
+                let is_open_four = self.five_points_on_line(point, direction, chess).len() >= 2;
+                self.remove(point);
+
+                if is_open_four {
+                    return true;
+                }
+            }
+        }
+
+        return false;
+    }
+
+    fn five_points(&self, chess: ChessType) -> Vec<Coord> {
+        return self.empty_points().into_iter()
+            .filter(|coord| self.makes_five(*coord, chess)).collect();
+    }
+
+    // the five points made by the chess at coord lie on its lines
+    fn five_points_through(&self, coord: Coord, chess: ChessType) -> Vec<Coord> {
+        let mut points = Vec::new();
+        for &direction in DIRECTIONS.iter() {
+            points.extend(self.five_points_on_line(coord, direction, chess));
+        }
+
+        return points;
+    }
+
+    fn five_points_on_line(&self, coord: Coord, direction: (isize, isize),
+                           chess: ChessType) -> Vec<Coord> {
+        return self.points_on_line(coord, direction).into_iter()
+            .filter(|point| self.makes_five_on_line(*point, direction, chess)).collect();
+    }
+
+    fn makes_five(&self, coord: Coord, chess: ChessType) -> bool {
+        return DIRECTIONS.iter()
+            .any(|direction| self.makes_five_on_line(coord, *direction, chess));
+    }
+
+    fn makes_five_on_line(&self, coord: Coord, direction: (isize, isize),
+                          chess: ChessType) -> bool {
+        let backward = (-direction.0, -direction.1);
+        let (forward_count, forward_end) = self.run_from(coord, direction, chess);
+        let (backward_count, backward_end) = self.run_from(coord, backward, chess);
+        return self.rule.is_winning_run(chess, 1 + forward_count + backward_count,
+                                        [forward_end, backward_end]);
+    }
+
+    // the chess in a row from coord and the cross point just beyond them,
+    // None at the edge
+    fn run_from(&self, coord: Coord, direction: (isize, isize),
+                chess: ChessType) -> (usize, Option<CrossPointType>) {
+        let mut count = 0;
+        let mut next = self.step(coord, direction);
+        while let Some(point) = next {
+            match self.chess_at(point) {
+                Some(point_chess) if point_chess == chess => {},
+                Some(point_chess) => return (count, Some(CptChess(point_chess))),
+                None => return (count, Some(CptEmpty)),
+            }
+            count += 1;
+            next = self.step(point, direction);
+        }
+
+        return (count, None);
+    }
+
+    fn is_forbidden(&self, coord: Coord, chess: ChessType) -> bool {
+        if self.rule != GrRenju || chess != CtBlack {
+            return false;
+        }
+
+        let cross_points = self.cells.borrow().iter().map(|cell| match *cell {
+            Some(chess) => CptChess(chess),
+            None => CptEmpty,
+        }).collect();
+        return ForbiddenChecker::create_with_cross_points(self.size, cross_points)
+            .check(coord).is_some();
+    }
+
+    // the empty cross points up to four away from coord on its four lines
+    fn line_points(&self, coord: Coord) -> Vec<Coord> {
+        let mut points = Vec::new();
+        for &direction in DIRECTIONS.iter() {
+            points.extend(self.points_on_line(coord, direction));
+        }
+
+        return points;
+    }
+
+    fn points_on_line(&self, coord: Coord, direction: (isize, isize)) -> Vec<Coord> {
+        let mut points = Vec::new();
+        for &sign in [1, -1].iter() {
+            let mut next = self.step(coord, (direction.0 * sign, direction.1 * sign));
+            for _ in 0..4 {
+                let point = match next {
+                    Some(point) => point,
+                    None => break,
+                };
+                if self.chess_at(point).is_none() {
+                    points.push(point);
+                }
+                next = self.step(point, (direction.0 * sign, direction.1 * sign));
+            }
+        }
+
+        return points;
+    }
+
+    // five cross points in a row from coord, None past the edge
+    fn window(&self, coord: Coord, direction: (isize, isize)) -> Option<Vec<Coord>> {
+        let mut window = vec![coord];
+        for _ in 1..5 {
+            let last = *window.last().unwrap();
+            window.push(self.step(last, direction)?);
+        }
+
+        return Some(window);
+    }
+
+    // a threat needs chess of its own on the line, the empty cross points
+    // far from them are left out
+    fn points_near(&self, chess: ChessType) -> Vec<Coord> {
+        let mut is_near = vec![false; self.size * self.size];
+        for row in 0..self.size {
+            for col in 0..self.size {
+                if self.chess_at(Coord{row, col}) == Some(chess) {
+                    for point in self.line_points(Coord{row, col}) {
+                        is_near[point.row * self.size + point.col] = true;
+                    }
+                }
+            }
+        }
+
+        return self.empty_points().into_iter()
+            .filter(|coord| is_near[coord.row * self.size + coord.col]).collect();
+    }
+
+    fn empty_points(&self) -> Vec<Coord> {
+        let mut points = Vec::new();
+        for row in 0..self.size {
+            for col in 0..self.size {
+                if self.chess_at(Coord{row, col}).is_none() {
+                    points.push(Coord{row, col});
+                }
+            }
+        }
+
+        return points;
+    }
+
+    fn step(&self, coord: Coord, direction: (isize, isize)) -> Option<Coord> {
+        let row = coord.row as isize + direction.0;
+        let col = coord.col as isize + direction.1;
+        if row < 0 || col < 0 || row >= self.size as isize || col >= self.size as isize {
+            return None;
+        }
+
+        return Some(Coord{row: row as usize, col: col as usize});
+    }
+
+    fn chess_at(&self, coord: Coord) -> Option<ChessType> {
+        return self.cells.borrow()[coord.row * self.size + coord.col];
+    }
+
+    fn put(&self, coord: Coord, chess: ChessType) {
+        self.cells.borrow_mut()[coord.row * self.size + coord.col] = Some(chess);
+        self.hash.set(self.hash.get() ^ self.zobrist.key(coord, chess));
+    }
+
+    fn remove(&self, coord: Coord) {
+        let chess = self.chess_at(coord).unwrap();
+        self.cells.borrow_mut()[coord.row * self.size + coord.col] = None;
+        self.hash.set(self.hash.get() ^ self.zobrist.key(coord, chess));
+    }
+}
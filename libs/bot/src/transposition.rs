@@ -0,0 +1,137 @@
+use std::cell::{Cell, RefCell};
+use std::mem;
+
+use backend::board::*;
+
+pub const DEFAULT_TABLE_SIZE_MB: usize = 4;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum BoundType {
+    // the score is the value of the position
+    BtExact,
+    // the search failed high, the value is at least the score
+    BtLower,
+    // the search failed low, the value is at most the score
+    BtUpper,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct TableEntry {
+    pub hash: u64,
+    pub depth: usize,
+    pub bound: BoundType,
+    pub score: i32,
+    pub best_move: Option<Coord>,
+    // the search which stored the entry, older ones are replaced first
+    generation: u8,
+}
+
+impl TableEntry {
+    pub fn new(hash: u64, depth: usize, bound: BoundType, score: i32,
+               best_move: Option<Coord>) -> TableEntry {
+        return TableEntry { hash, depth, bound, score, best_move, generation: 0 };
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct TableStats {
+    pub probes: u64,
+    pub hits: u64,
+    pub stores: u64,
+}
+
+impl TableStats {
+    pub fn hit_rate(&self) -> f64 {
+        match self.probes {
+            0 => return 0.0,
+            probes => return self.hits as f64 / probes as f64,
+        }
+    }
+}
+
+// a fixed count of slots indexed by the hash, one entry in each, so a
+// position may push another one out of its slot
+//
+// an entry is replaced by one of the same position, by one from a newer
+// search, or else by one searched at least as deep
+pub struct TranspositionTable {
+    entries: RefCell<Vec<Option<TableEntry>>>,
+    generation: Cell<u8>,
+    stats: Cell<TableStats>,
+}
+
+impl TranspositionTable {
+    pub fn new() -> TranspositionTable {
+        return TranspositionTable::create_with_size_mb(DEFAULT_TABLE_SIZE_MB);
+    }
+
+    // the table always has one slot at least
+    pub fn create_with_size_mb(size_mb: usize) -> TranspositionTable {
+        let capacity = (size_mb * 1024 * 1024 / mem::size_of::<Option<TableEntry>>()).max(1);
+
+        return TranspositionTable {
+            entries: RefCell::new(vec![None; capacity]),
+            generation: Cell::new(0),
+            stats: Cell::new(TableStats { probes: 0, hits: 0, stores: 0 }),
+        };
+    }
+
+    pub fn capacity(&self) -> usize {
+        return self.entries.borrow().len();
+    }
+
+    pub fn probe(&self, hash: u64) -> Option<TableEntry> {
+        let entry = self.entries.borrow()[self.index(hash)]
+            .filter(|entry| entry.hash == hash);
+
+        let mut stats = self.stats.get();
+        stats.probes += 1;
+        if entry.is_some() {
+            stats.hits += 1;
+        }
+        self.stats.set(stats);
+
+        return entry;
+    }
+
+    pub fn store(&self, entry: TableEntry) {
+        let index = self.index(entry.hash);
+        let generation = self.generation.get();
+        let mut entries = self.entries.borrow_mut();
+
+        let replace = match entries[index] {
+            Some(old) => old.hash == entry.hash || old.generation != generation
+                || entry.depth >= old.depth,
+            None => true,
+        };
+        if !replace {
+            return;
+        }
+
+        entries[index] = Some(TableEntry { generation, ..entry });
+        let mut stats = self.stats.get();
+        stats.stores += 1;
+        self.stats.set(stats);
+    }
+
+    // the entries are kept for the next search, but give way to its own
+    pub fn new_search(&self) {
+        self.generation.set(self.generation.get().wrapping_add(1));
+        self.stats.set(TableStats { probes: 0, hits: 0, stores: 0 });
+    }
+
+    pub fn clear(&self) {
+        for entry in self.entries.borrow_mut().iter_mut() {
+            *entry = None;
+        }
+        self.stats.set(TableStats { probes: 0, hits: 0, stores: 0 });
+    }
+
+    pub fn stats(&self) -> TableStats {
+        return self.stats.get();
+    }
+
+    fn index(&self, hash: u64) -> usize {
+        return (hash % self.entries.borrow().len() as u64) as usize;
+    }
+}
@@ -0,0 +1,147 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+// feeds the whole script to the engine and returns what it wrote
+fn run_engine(script: &str) -> Vec<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pbrain-gomoku"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(script.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    return String::from_utf8(output.stdout).unwrap().lines().map(|line| line.to_string()).collect();
+}
+
+fn parse_move(answer: &str) -> (usize, usize) {
+    let fields: Vec<usize> = answer.split(',').map(|field| field.parse().unwrap()).collect();
+    assert_eq!(fields.len(), 2, "{}", answer);
+    return (fields[0], fields[1]);
+}
+
+#[test]
+fn begin_and_turn() {
+    let output = run_engine("START 15\n\
+                             INFO timeout_turn 500\n\
+                             INFO max_memory 83886080\n\
+                             BEGIN\n\
+                             TURN 8,8\n\
+                             END\n");
+
+    assert_eq!(output.len(), 3);
+    assert_eq!(output[0], "OK");
+    assert_eq!(parse_move(&output[1]), (7, 7));
+
+    let reply = parse_move(&output[2]);
+    assert!(reply != (7, 7) && reply != (8, 8));
+    assert!(reply.0 < 15 && reply.1 < 15);
+}
+
+#[test]
+fn board_with_five_to_make() {
+    // the own chess is four in a row on y 7, the opponent's on y 8
+    let output = run_engine("START 15\n\
+                             INFO timeout_turn 500\n\
+                             BOARD\n\
+                             3,7,1\n3,8,2\n4,7,1\n4,8,2\n5,7,1\n5,8,2\n6,7,1\n6,8,2\n\
+                             DONE\n\
+                             END\n");
+
+    assert_eq!(output.len(), 2);
+    let reply = parse_move(&output[1]);
+    assert!(reply == (2, 7) || reply == (7, 7), "{:?}", reply);
+}
+
+#[test]
+fn block_five() {
+    // white answers the first turn and then has to block x 7
+    let output = run_engine("START 15\n\
+                             INFO timeout_turn 500\n\
+                             BOARD\n\
+                             7,3,2\n7,4,2\n7,5,2\n7,6,2\n0,0,1\n14,14,1\n1,14,1\n\
+                             DONE\n\
+                             END\n");
+
+    assert_eq!(output.len(), 2);
+    let reply = parse_move(&output[1]);
+    assert!(reply == (7, 2) || reply == (7, 7), "{:?}", reply);
+}
+
+// the search deepens as far as the time of the turn allows and no further
+#[test]
+fn timeout_turn() {
+    let start = Instant::now();
+    let output = run_engine("START 15\n\
+                             INFO timeout_turn 300\n\
+                             BOARD\n\
+                             7,7,2\n8,7,1\n8,8,2\n6,6,1\n9,9,2\n6,8,1\n\
+                             DONE\n\
+                             END\n");
+
+    assert_eq!(output.len(), 2);
+    parse_move(&output[1]);
+    assert!(start.elapsed() < Duration::from_secs(3), "{:?}", start.elapsed());
+}
+
+// the time left in the match caps the time of a turn
+#[test]
+fn timeout_match() {
+    let start = Instant::now();
+    let output = run_engine("START 15\n\
+                             INFO timeout_turn 30000\n\
+                             INFO timeout_match 180000\n\
+                             INFO time_left 4000\n\
+                             BOARD\n\
+                             7,7,2\n8,7,1\n8,8,2\n6,6,1\n9,9,2\n6,8,1\n\
+                             DONE\n\
+                             END\n");
+
+    assert_eq!(output.len(), 2);
+    parse_move(&output[1]);
+    assert!(start.elapsed() < Duration::from_secs(3), "{:?}", start.elapsed());
+}
+
+// black leaves out the double three at 7,7 under renju
+#[test]
+fn renju_rule() {
+    let output = run_engine("START 15\n\
+                             INFO rule 4\n\
+                             INFO timeout_turn 500\n\
+                             BOARD\n\
+                             5,7,1\n9,9,2\n6,7,1\n4,9,2\n7,5,1\n9,4,2\n7,6,1\n10,10,2\n\
+                             DONE\n\
+                             END\n");
+
+    assert_eq!(output.len(), 2);
+    assert!(parse_move(&output[1]) != (7, 7));
+}
+
+#[test]
+fn other_commands() {
+    let output = run_engine("TURN 1,1\n\
+                             START 3\n\
+                             START 15\n\
+                             ABOUT\n\
+                             SWAP2BOARD\n\
+                             INFO rule 1\n\
+                             TURN 15,0\n\
+                             TAKEBACK 0,0\n\
+                             RESTART\n\
+                             END\n\
+                             START 15\n");
+
+    assert_eq!(output.len(), 8);
+    assert!(output[0].starts_with("ERROR"));
+    assert!(output[1].starts_with("ERROR"));
+    assert_eq!(output[2], "OK");
+    assert!(output[3].starts_with("name=\"gomoku\""));
+    assert_eq!(output[4], "UNKNOWN SWAP2BOARD");
+    assert!(output[5].starts_with("ERROR"));
+    assert!(output[6].starts_with("ERROR"));
+    // nothing is read after END
+    assert_eq!(output[7], "OK");
+}
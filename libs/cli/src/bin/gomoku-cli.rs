@@ -0,0 +1,124 @@
+extern crate bot;
+extern crate cli;
+
+use std::env;
+use std::io;
+use std::io::{BufRead, Write};
+use std::process;
+
+use bot::difficulty::*;
+use bot::players::*;
+use cli::command_line::*;
+
+use bot::difficulty::Difficulty::*;
+use bot::players::PlayerKind::*;
+
+const USAGE: &'static str = "\
+usage: gomoku-cli [--black human|bot] [--white human|bot]
+                  [--difficulty easy|normal|hard] [--depth <n>]
+                  [--load <path>] [--analyse]
+
+black is a human and white the bot unless told otherwise, the bot plays
+at full strength unless a difficulty is given, the hints and the analysis
+always are, a depth goes over the one of the difficulty and also limits
+the hints, with --analyse the loaded game is gone through move by move
+and the program leaves";
+
+fn main() {
+    let mut black = PkHuman;
+    let mut white = PkBot;
+    let mut difficulty = None;
+    let mut depth: Option<usize> = None;
+    let mut load_path = None;
+    let mut analyse = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--black" => black = parse_player(args.next()),
+            "--white" => white = parse_player(args.next()),
+            "--difficulty" => difficulty = Some(parse_difficulty(args.next())),
+            "--depth" => depth = Some(args.next().and_then(|n| n.parse().ok()).unwrap_or_else(|| usage())),
+            "--load" => load_path = Some(args.next().unwrap_or_else(|| usage())),
+            "--analyse" => analyse = true,
+            _ => usage(),
+        }
+    }
+
+    let command_line = CommandLine::create_with_players(black, white);
+    if let Some(difficulty) = difficulty {
+        command_line.bot().set_difficulty(difficulty);
+    }
+    if let Some(depth) = depth {
+        command_line.bot().set_max_depth(depth.max(1));
+        command_line.hint_bot().set_max_depth(depth.max(1));
+    }
+    if let Some(path) = load_path {
+        if let Err(error) = command_line.session().load_game(&path) {
+            eprintln!("error: {}", error.message);
+            process::exit(1);
+        }
+    }
+
+    if analyse {
+        match command_line.analyse() {
+            Ok(text) => print!("{}", text),
+            Err(error) => {
+                eprintln!("error: {}", error.message);
+                process::exit(1);
+            },
+        }
+        return;
+    }
+
+    run(&command_line, black == PkBot && white == PkBot);
+}
+
+// reads commands until quit or the end of the input, two bots play the
+// game out without waiting for any
+fn run(command_line: &CommandLine, bots_only: bool) {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    println!("{}", command_line.render_board());
+    loop {
+        print!("{}", command_line.play_bots());
+        if bots_only {
+            return;
+        }
+
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => return,
+        };
+
+        match command_line.handle_command(&line) {
+            Some(text) => println!("{}", text),
+            None => return,
+        }
+    }
+}
+
+fn parse_player(arg: Option<String>) -> PlayerKind {
+    match arg.as_ref().map(|arg| arg.as_str()) {
+        Some("human") => return PkHuman,
+        Some("bot") => return PkBot,
+        _ => usage(),
+    }
+}
+
+fn parse_difficulty(arg: Option<String>) -> Difficulty {
+    match arg.as_ref().map(|arg| arg.as_str()) {
+        Some("easy") => return DfEasy,
+        Some("normal") => return DfNormal,
+        Some("hard") => return DfHard,
+        _ => usage(),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
@@ -0,0 +1,241 @@
+use std::rc::Rc;
+
+use backend::board::*;
+use backend::cross_point::*;
+use backend::game_session::*;
+use backend::utils::*;
+use bot::players::*;
+use bot::search::*;
+
+use backend::cross_point::ChessType::*;
+
+const HELP: &'static str = "\
+moves are written like h8, the columns are letters from the left and the
+rows are numbers from the bottom
+
+  <move>        put a chess for the side to move
+  undo          take back the last move
+  restart       start a new game
+  hint          show the move of the bot
+  board         show the board again
+  save <path>   save the game, the format follows the extension
+  load <path>   load a game
+  help          show this text
+  quit          leave";
+
+// the game in a terminal, the board is drawn as text and the commands
+// come in line by line, each command returns what should be printed
+pub struct CommandLine {
+    session: Rc<GameSession>,
+    // searches for the hint and the analysis at full strength, the
+    // difficulty only weakens the bot of the players
+    hint: Bot,
+    players: Players,
+}
+
+impl CommandLine {
+    pub fn create_with_players(black: PlayerKind, white: PlayerKind) -> CommandLine {
+        let session = Rc::new(GameSession::new());
+        let hint = Bot::create_with_rule(session.board(), session.rule());
+        let players = Players::create_with_session(session.clone(), black, white);
+
+        return CommandLine {
+            session,
+            hint,
+            players,
+        };
+    }
+
+    pub fn session(&self) -> &GameSession {
+        return &self.session;
+    }
+
+    pub fn bot(&self) -> &Bot {
+        return self.players.bot();
+    }
+
+    pub fn hint_bot(&self) -> &Bot {
+        return &self.hint;
+    }
+
+    // None once the user wants to leave
+    pub fn handle_command(&self, line: &str) -> Option<String> {
+        let line = line.trim();
+        let (command, argument) = match line.find(' ') {
+            Some(index) => (&line[..index], line[index + 1..].trim()),
+            None => (line, ""),
+        };
+
+        let result = match command {
+            "" => Ok(String::new()),
+            "quit" | "exit" => return None,
+            "help" => Ok(HELP.to_string()),
+            "board" => Ok(self.render_board()),
+            "undo" => self.on_back_move(),
+            "restart" => self.on_restart_game(),
+            "hint" => self.on_next_step(),
+            "save" => self.session.save_game(argument)
+                .map(|_| format!("saved to {}", argument)),
+            "load" => self.session.load_game(argument)
+                .map(|_| self.render_board()),
+            _ => self.on_move(line),
+        };
+
+        match result {
+            Ok(text) => return Some(text),
+            Err(error) => return Some(format!("error: {}", error.message)),
+        }
+    }
+
+    pub fn is_bot_to_move(&self) -> bool {
+        return self.players.is_bot_to_move();
+    }
+
+    // the bots move until a human is to move or the game is over, every
+    // move is printed with the board after it
+    pub fn play_bots(&self) -> String {
+        let mut text = String::new();
+        loop {
+            let step = match self.players.play_bot_move() {
+                Ok(Some(step)) => step,
+                Ok(None) => break,
+                Err(error) => {
+                    text.push_str(&format!("error: {}\n", error.message));
+                    break;
+                },
+            };
+
+            text.push_str(&format!("{} plays {}\n", chess_name(step.chess),
+                                   self.notation(step.coord)));
+            text.push_str(&self.render_board());
+            text.push('\n');
+        }
+
+        return text;
+    }
+
+    // each move of the main line next to the one the bot would play there,
+    // the scores are for the side to move
+    pub fn analyse(&self) -> Result<String, Error> {
+        let steps = self.session.steps();
+        let mut text = String::new();
+
+        for (number, step) in steps.iter().enumerate() {
+            self.session.jump_to_move(number)?;
+            let result = self.hint.search(step.chess);
+            let played = self.notation(step.coord);
+            let line = match result.best_move {
+                Some(best) => {
+                    let mark = match best == step.coord {
+                        true => "  ",
+                        false => " ?",
+                    };
+                    format!("{:>3}. {:<5} {:<4}{} bot {:<4} {}\n", number + 1,
+                            chess_name(step.chess), played, mark, self.notation(best),
+                            score_text(result.score))
+                },
+                None => format!("{:>3}. {:<5} {}\n", number + 1, chess_name(step.chess), played),
+            };
+            text.push_str(&line);
+        }
+
+        self.session.jump_to_move(steps.len())?;
+        text.push_str(&self.result_text());
+        return Ok(text);
+    }
+
+    // 'X' for black and 'O' for white, the last move in brackets
+    pub fn render_board(&self) -> String {
+        let size = self.session.board_size();
+        let mut cells = vec![vec!['.'; size]; size];
+        for step in self.session.setup_stones().iter().chain(self.session.steps().iter()) {
+            cells[step.coord.row][step.coord.col] = match step.chess {
+                CtBlack => 'X',
+                CtWhite => 'O',
+            };
+        }
+        let last = self.session.last_step().map(|step| (step.coord.row, step.coord.col));
+
+        let letters: String = (0..size)
+            .map(|col| format!(" {}", (b'a' + col as u8) as char)).collect();
+        let mut text = format!("   {}\n", letters);
+        for row in 0..size {
+            let mut line = format!("{:>2} ", size - row);
+            for col in 0..size {
+                line.push(match last {
+                    Some((last_row, last_col)) if last_row == row && last_col == col => '(',
+                    Some((last_row, last_col)) if last_row == row && last_col + 1 == col => ')',
+                    _ => ' ',
+                });
+                line.push(cells[row][col]);
+            }
+            if last == Some((row, size - 1)) {
+                line.push(')');
+            }
+            text.push_str(&format!("{}\n", line.trim_end()));
+        }
+
+        text.push_str(&self.result_text());
+        return text;
+    }
+
+    fn on_move(&self, notation: &str) -> Result<String, Error> {
+        let coord = Coord::from_notation(notation, self.session.board_size())?;
+        self.players.put_human_chess(coord)?;
+        return Ok(self.render_board());
+    }
+
+    fn on_back_move(&self) -> Result<String, Error> {
+        self.players.take_back_human_move()?;
+        return Ok(self.render_board());
+    }
+
+    fn on_restart_game(&self) -> Result<String, Error> {
+        self.session.restart()?;
+        return Ok(self.render_board());
+    }
+
+    fn on_next_step(&self) -> Result<String, Error> {
+        if self.session.is_over() {
+            return Ok("no hint, the game is over".to_string());
+        }
+
+        match self.hint.search(self.session.next_chess()).best_move {
+            Some(coord) => return Ok(format!("hint: {} (table hit rate {:.2})", self.notation(coord),
+                                             self.hint.table_stats().hit_rate())),
+            None => return Ok("no hint, the board is full".to_string()),
+        }
+    }
+
+    fn result_text(&self) -> String {
+        if let Some(winner) = self.session.winner() {
+            return format!("{} wins\n", chess_name(winner));
+        } else if self.session.is_draw() {
+            return "draw\n".to_string();
+        }
+
+        return format!("{} to move\n", chess_name(self.session.next_chess()));
+    }
+
+    // the coords all come from the board, so each of them has a notation
+    fn notation(&self, coord: Coord) -> String {
+        return coord.to_notation(self.session.board_size()).unwrap_or_default();
+    }
+}
+
+fn chess_name(chess: ChessType) -> &'static str {
+    match chess {
+        CtBlack => return "black",
+        CtWhite => return "white",
+    }
+}
+
+fn score_text(score: i32) -> String {
+    if score >= WIN_SCORE - MAX_PLY {
+        return "wins".to_string();
+    } else if score <= -WIN_SCORE + MAX_PLY {
+        return "loses".to_string();
+    }
+
+    return format!("score {}", score);
+}
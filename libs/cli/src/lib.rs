@@ -0,0 +1,7 @@
+extern crate backend;
+extern crate bot;
+
+pub mod command_line;
+
+#[cfg(test)]
+mod tests;
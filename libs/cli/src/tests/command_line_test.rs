@@ -0,0 +1,131 @@
+use std::env;
+use std::fs;
+
+use backend::board::*;
+use bot::players::*;
+
+use backend::cross_point::ChessType::*;
+use bot::difficulty::Difficulty::*;
+use bot::players::PlayerKind::*;
+
+use super::super::command_line::*;
+
+fn create_command_line(black: PlayerKind, white: PlayerKind) -> CommandLine {
+    let command_line = CommandLine::create_with_players(black, white);
+    command_line.bot().set_max_depth(1);
+    command_line.hint_bot().set_max_depth(1);
+    return command_line;
+}
+
+#[test]
+fn render_board() {
+    let command_line = create_command_line(PkHuman, PkHuman);
+    let text = command_line.render_board();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 17);
+    assert_eq!(lines[0], "    a b c d e f g h i j k l m n o");
+    assert_eq!(lines[1], "15  . . . . . . . . . . . . . . .");
+    assert_eq!(lines[15], " 1  . . . . . . . . . . . . . . .");
+    assert_eq!(lines[16], "black to move");
+
+    // the last move is in brackets, also at the edge
+    command_line.handle_command("h8").unwrap();
+    command_line.handle_command("o1").unwrap();
+    let text = command_line.render_board();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[8], " 8  . . . . . . . X . . . . . . .");
+    assert_eq!(lines[15], " 1  . . . . . . . . . . . . . .(O)");
+    assert_eq!(lines[16], "black to move");
+
+    command_line.handle_command("undo").unwrap();
+    let text = command_line.render_board();
+    assert_eq!(text.lines().nth(8), Some(" 8  . . . . . . .(X). . . . . . ."));
+    assert_eq!(text.lines().nth(16), Some("white to move"));
+}
+
+#[test]
+fn handle_command() {
+    let command_line = create_command_line(PkHuman, PkHuman);
+    assert_eq!(command_line.handle_command("quit"), None);
+    assert_eq!(command_line.handle_command("  "), Some(String::new()));
+    assert!(command_line.handle_command("help").unwrap().contains("take back the last move"));
+    assert!(command_line.handle_command("z99").unwrap().starts_with("error: "));
+
+    assert_eq!(command_line.handle_command("h8"), Some(command_line.render_board()));
+    assert!(command_line.handle_command("h8").unwrap().starts_with("error: "));
+    assert_eq!(command_line.session().steps().len(), 1);
+    assert!(command_line.handle_command("hint").unwrap().starts_with("hint: "));
+
+    command_line.handle_command("restart").unwrap();
+    assert_eq!(command_line.session().steps().len(), 0);
+    assert_eq!(command_line.handle_command("undo").unwrap(), command_line.render_board());
+
+    for notation in ["a1", "b1", "a2", "b2", "a3", "b3", "a4", "b4", "a5"].iter() {
+        command_line.handle_command(notation).unwrap();
+    }
+    assert_eq!(command_line.session().winner(), Some(CtBlack));
+    assert!(command_line.render_board().ends_with("black wins\n"));
+    assert_eq!(command_line.handle_command("hint").unwrap(), "no hint, the game is over");
+
+    let path = env::temp_dir().join(format!("{}_command_line_test.sgf", std::process::id()));
+    let path = path.to_str().unwrap();
+    assert_eq!(command_line.handle_command(&format!("save {}", path)).unwrap(),
+               format!("saved to {}", path));
+
+    let loaded = create_command_line(PkHuman, PkHuman);
+    loaded.handle_command(&format!("load {}", path)).unwrap();
+    assert_eq!(loaded.session().steps(), command_line.session().steps());
+    fs::remove_file(path).unwrap();
+}
+
+// a human never moves for the bot, and takes back the reply of the bot
+// together with the own move
+#[test]
+fn against_bot() {
+    let command_line = create_command_line(PkHuman, PkBot);
+    command_line.handle_command("h8").unwrap();
+    assert_eq!(command_line.is_bot_to_move(), true);
+    assert!(command_line.handle_command("j9").unwrap().starts_with("error: "));
+
+    let text = command_line.play_bots();
+    assert!(text.starts_with("white plays "));
+    assert_eq!(command_line.session().steps().len(), 2);
+    assert_eq!(command_line.play_bots(), "");
+
+    command_line.handle_command("undo").unwrap();
+    assert_eq!(command_line.session().steps().len(), 0);
+}
+
+// the difficulty weakens the bot the human plays, not the hints
+#[test]
+fn full_strength_hint() {
+    let command_line = CommandLine::create_with_players(PkHuman, PkBot);
+    let max_candidates = command_line.hint_bot().max_candidates();
+    command_line.bot().set_difficulty(DfEasy);
+    assert!(command_line.bot().max_candidates() < max_candidates);
+    assert_eq!(command_line.hint_bot().max_candidates(), max_candidates);
+
+    command_line.hint_bot().set_max_depth(1);
+    assert!(command_line.handle_command("hint").unwrap().starts_with("hint: "));
+}
+
+#[test]
+fn analyse() {
+    let command_line = create_command_line(PkHuman, PkHuman);
+    for notation in ["h8", "j10", "h9"].iter() {
+        command_line.handle_command(notation).unwrap();
+    }
+
+    let text = command_line.analyse().unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("  1. black h8 "));
+    assert!(lines[1].starts_with("  2. white j10 "));
+    assert!(lines[2].starts_with("  3. black h9 "));
+    assert!(lines[0..3].iter().all(|line| line.contains(" bot ")));
+    assert_eq!(lines[3], "white to move");
+
+    // the game is back at its last move
+    assert_eq!(command_line.session().steps().len(), 3);
+    assert_eq!(command_line.session().last_step().map(|step| step.coord), Some(Coord{row: 6, col: 7}));
+}
@@ -0,0 +1 @@
+mod command_line_test;
@@ -1,64 +1,452 @@
+use std::rc::Rc;
+
 use sciter::dom::Element;
 use sciter::dom::event;
 use sciter::HELEMENT;
 use sciter::value::Value;
 
-use board_controller::*;
+use backend::board::*;
+use backend::cross_point::*;
+use backend::forbidden_checker::*;
+use backend::game_session::*;
+use backend::notation::*;
+use backend::opening::*;
+use backend::utils::*;
+use bot::difficulty::*;
+use bot::players::*;
+use bot::search::*;
+use bot::threat::*;
+
+use backend::cross_point::ChessType::*;
+use backend::opening::OpeningAction::*;
+use backend::opening::OpeningPhase::*;
+use backend::opening::OpeningRule::*;
+use backend::opening::Player::*;
+use bot::difficulty::Difficulty::*;
+use bot::players::PlayerKind::*;
+use bot::threat::SolveStatus::*;
 
+// turns the script calls into calls of the game session and the results
+// into values for the script, the chess are "black" and "white" there
 pub struct EventHandler {
     root: Option<Element>,
-    board_controller: BoardController,
+    session: Rc<GameSession>,
+    // searches the board of the session for the hint, its table is kept
+    // from one hint to the next
+    bot: Bot,
+    // the human and the bot playing against, the bot is weakened by the
+    // chosen difficulty
+    players: Players,
 }
 
 impl EventHandler {
     pub fn new() -> EventHandler {
+        let session = Rc::new(GameSession::new());
+        let bot = Bot::create_with_rule(session.board(), session.rule());
+        let players = Players::create_with_session(session.clone(), PkHuman, PkBot);
+
         return EventHandler{
             root: None,
-            board_controller: BoardController::new(),
+            session,
+            bot,
+            players,
+        };
+    }
+
+    // color is the one of the human, "both" when no bot plays, the bot
+    // moves at once when it is to move
+    fn on_game_config_changed(&self, color: String, difficulty: String) -> Value {
+        let (black, white) = match (color.as_str(), chess_from_str(&color)) {
+            ("both", _) => (PkHuman, PkHuman),
+            (_, Some(CtBlack)) => (PkHuman, PkBot),
+            (_, Some(CtWhite)) => (PkBot, PkHuman),
+            (_, None) => return error_value("unknown chess color"),
+        };
+        let difficulty = match difficulty_from_str(&difficulty) {
+            Some(difficulty) => difficulty,
+            None => return error_value("unknown bot difficulty"),
+        };
+
+        self.players.set_players(black, white);
+        self.players.bot().set_difficulty(difficulty);
+        return self.on_bot_move();
+    }
+
+    // the bot plays when it is to move after the opening, there is no row
+    // in the value when it did not
+    fn on_bot_move(&self) -> Value {
+        let mut value = Value::new();
+        let step = match self.players.play_bot_move() {
+            Ok(Some(step)) => step,
+            Ok(None) => return value,
+            Err(error) => {
+                value.set_item("error", error.message);
+                return value;
+            },
         };
+
+        value.set_item("row", step.coord.row as i32);
+        value.set_item("col", step.coord.col as i32);
+        value.set_item("current_chess", chess_to_str(step.chess));
+        self.set_result_items(&mut value);
+        return value;
     }
 
     fn on_cross_point_click(&self, row: i32, col: i32) -> Value {
         let mut value = Value::new();
+        let coord = to_coord(row, col);
 
-        value.set_item("current_chess", self.board_controller.get_current_chess());
-        self.board_controller.put_chess(row, col);
+        if !self.session.is_opening_finished() {
+            match self.session.put_opening_chess(coord) {
+                Ok(chess) => value.set_item("current_chess", chess_to_str(chess)),
+                Err(error) => value.set_item("error", error.message),
+            }
+            value.set_item("opening", self.get_opening_status());
+            return value;
+        }
 
-        value.set_item("have_game_over", self.board_controller.have_game_over());
-        if let Some(winner) = self.board_controller.get_winner() {
-            value.set_item("winner", winner);
+        value.set_item("current_chess", chess_to_str(self.session.next_chess()));
+        if let Err(error) = self.players.put_human_chess(coord) {
+            match error.kind {
+                ErrorKind::MoveForbidden(forbidden) => value.set_item(
+                    "forbidden", forbidden_to_str(forbidden)),
+                _ => value.set_item("error", error.message),
+            }
+            return value;
         }
 
+        self.set_result_items(&mut value);
         return value;
     }
 
     fn on_back_move(&self) -> Value {
+        let steps_before = self.session.steps();
+        let result = self.players.take_back_human_move();
+        return self.navigation_value(&steps_before, result);
+    }
+
+    fn on_forward_move(&self) -> Value {
+        let steps_before = self.session.steps();
+        let result = self.session.forward_move();
+        return self.navigation_value(&steps_before, result.map(|_| ()));
+    }
+
+    fn on_next_variation(&self) -> Value {
+        let steps_before = self.session.steps();
+        let result = self.session.next_variation();
+        return self.navigation_value(&steps_before, result.map(|_| ()));
+    }
+
+    fn on_undo_to_my_last_move(&self, chess: String) -> Value {
+        let steps_before = self.session.steps();
+        let result = match chess_from_str(&chess) {
+            Some(chess) => self.session.undo_to_my_last_move(chess),
+            None => Err(Error::create_with_detail(ErrorKind::WrongTurn, "unknown chess color")),
+        };
+        return self.navigation_value(&steps_before, result);
+    }
+
+    fn on_jump_to_move(&self, number: i32) -> Value {
+        let steps_before = self.session.steps();
+        let result = match number < 0 {
+            true => Err(Error::create_with_detail(ErrorKind::NodeNotFound,
+                                                  "can not jump before the first move")),
+            false => self.session.jump_to_move(number as usize),
+        };
+        return self.navigation_value(&steps_before, result);
+    }
+
+    // every cell which changed since steps_before is sent back together
+    // with the last chess, so that the ui only redraws those
+    fn navigation_value(&self, steps_before: &[CoordAndChess],
+                        result: Result<(), Error>) -> Value {
         let mut value = Value::new();
 
-        if let Some(coord) = self.board_controller.remove_last_chess() {
-            value.set_item("row", coord.0);
-            value.set_item("col", coord.1);
+        if let Err(error) = result {
+            value.set_item("error", error.message);
         }
 
-        if let Some(last_step) = self.board_controller.get_last_step() {
-            value.set_item("last_row", last_step.0);
-            value.set_item("last_col", last_step.1);
-            value.set_item("last_chess", last_step.2);
+        let mut changed_value = Value::array(0);
+        for change in self.session.changed_cells(steps_before) {
+            let mut cell_value = self.coord_value(change.coord);
+            cell_value.set_item("chess", change.chess.map_or("empty", chess_to_str));
+            changed_value.push(cell_value);
         }
+        value.set_item("changed", changed_value);
+
+        if let Some(last_step) = self.session.last_step() {
+            value.set_item("last_row", last_step.coord.row as i32);
+            value.set_item("last_col", last_step.coord.col as i32);
+            value.set_item("last_chess", chess_to_str(last_step.chess));
+        }
+        value.set_item("move_count", self.session.steps().len() as i32);
+        value.set_item("current_chess", chess_to_str(self.session.next_chess()));
+        value.set_item("variations", self.session.variation_count() as i32);
+        self.set_result_items(&mut value);
+
         return value;
     }
 
     fn on_restart_game(&self) -> Value {
-        self.board_controller.restart_game();
-        return Value::new();
+        let mut value = Value::new();
+
+        if let Err(error) = self.session.restart() {
+            value.set_item("error", error.message);
+        }
+        return value;
+    }
+
+    fn on_save_game(&self, path: String) -> Value {
+        let mut value = Value::new();
+
+        if let Err(error) = self.session.save_game(&path) {
+            value.set_item("error", error.message);
+        }
+        return value;
+    }
+
+    // the steps are always sent back, the board may have been cleared
+    // even when loading failed
+    fn on_open_game(&self, path: String) -> Value {
+        let mut value = Value::new();
+
+        if let Err(error) = self.session.load_game(&path) {
+            value.set_item("error", error.message);
+        }
+
+        self.set_board_items(&mut value);
+        value.set_item("opening", self.get_opening_status());
+        return value;
+    }
+
+    fn on_begin_setup(&self) -> Value {
+        let mut value = Value::new();
+
+        if let Err(error) = self.session.begin_setup() {
+            value.set_item("error", error.message);
+        }
+        value.set_item("setup", self.session.is_in_setup());
+        value.set_item("opening", self.get_opening_status());
+        return value;
+    }
+
+    // chess is "black", "white" or "empty"
+    fn on_setup_chess(&self, row: i32, col: i32, chess: String) -> Value {
+        let mut value = Value::new();
+
+        let result = match (chess.as_str(), chess_from_str(&chess)) {
+            ("empty", _) => self.session.setup_chess(to_coord(row, col), None),
+            (_, Some(chess_type)) => self.session.setup_chess(to_coord(row, col), Some(chess_type)),
+            (_, None) => Err(Error::create_with_detail(ErrorKind::PositionInvalid,
+                                                       "unknown chess color")),
+        };
+        match result {
+            Ok(()) => {
+                let mut cell_value = self.coord_value(to_coord(row, col));
+                cell_value.set_item("chess", chess);
+                let mut changed_value = Value::array(0);
+                changed_value.push(cell_value);
+                value.set_item("changed", changed_value);
+            },
+            Err(error) => value.set_item("error", error.message),
+        }
+        return value;
+    }
+
+    fn on_finish_setup(&self, chess: String) -> Value {
+        let mut value = Value::new();
+
+        let result = match chess_from_str(&chess) {
+            Some(chess) => self.session.finish_setup(chess),
+            None => Err(Error::create_with_detail(ErrorKind::PositionInvalid,
+                                                  "unknown chess color")),
+        };
+        if let Err(error) = result {
+            value.set_item("error", error.message);
+        }
+        value.set_item("setup", self.session.is_in_setup());
+        value.set_item("current_chess", chess_to_str(self.session.next_chess()));
+        return value;
+    }
+
+    fn set_board_items(&self, value: &mut Value) {
+        value.set_item("setup", self.steps_value(&self.session.setup_stones()));
+        value.set_item("steps", self.steps_value(&self.session.steps()));
+        value.set_item("current_chess", chess_to_str(self.session.next_chess()));
+        value.set_item("variations", self.session.variation_count() as i32);
+        self.set_result_items(value);
+    }
+
+    fn set_result_items(&self, value: &mut Value) {
+        value.set_item("have_game_over", self.session.is_over());
+        value.set_item("is_draw", self.session.is_draw());
+        if let Some(winner) = self.session.winner() {
+            value.set_item("winner", chess_to_str(winner));
+
+            let mut line_value = Value::array(0);
+            for coord in self.session.winning_line().iter() {
+                line_value.push(self.coord_value(*coord));
+            }
+            value.set_item("winning_line", line_value);
+        }
+    }
+
+    fn on_opening_rule_changed(&self, rule: String) -> Value {
+        let result = match opening_rule_from_str(&rule) {
+            Some(rule) => self.session.set_opening_rule(rule),
+            None => Err(Error::create_with_detail(ErrorKind::OpeningActionInvalid,
+                                                  "unknown opening rule")),
+        };
+        return self.opening_result(result);
+    }
+
+    fn on_choose_color(&self, chess: String) -> Value {
+        let result = match chess_from_str(&chess) {
+            Some(chess) => self.session.choose_color(chess),
+            None => Err(Error::create_with_detail(ErrorKind::OpeningActionInvalid,
+                                                  "unknown chess color")),
+        };
+        return self.opening_result(result);
+    }
+
+    fn on_place_two(&self) -> Value {
+        return self.opening_result(self.session.place_two());
+    }
+
+    fn on_declare_alternatives(&self, count: i32) -> Value {
+        let result = match count < 0 {
+            true => Err(Error::create_with_detail(ErrorKind::OpeningActionInvalid,
+                                                  "declared count of fifth moves out of range")),
+            false => self.session.declare_alternatives(count as usize),
+        };
+        return self.opening_result(result);
+    }
+
+    fn on_offer_alternatives(&self) -> Value {
+        return self.opening_result(self.session.offer_alternatives());
+    }
+
+    fn on_select_alternative(&self, row: i32, col: i32) -> Value {
+        let mut value = Value::new();
+
+        match self.session.select_alternative(to_coord(row, col)) {
+            Ok(_) => value.set_item("current_chess", chess_to_str(CtBlack)),
+            Err(error) => value.set_item("error", error.message),
+        }
+
+        value.set_item("opening", self.get_opening_status());
+        return value;
     }
 
+    fn get_opening_status(&self) -> Value {
+        let mut value = Value::new();
+        let status = self.session.opening_status();
+
+        value.set_item("phase", opening_phase_to_str(status.phase));
+        value.set_item("player", match status.player {
+            PlFirst => "first",
+            PlSecond => "second",
+        });
+        value.set_item("current_chess", chess_to_str(self.session.next_chess()));
+
+        let mut actions_value = Value::array(0);
+        for action in status.actions {
+            actions_value.push(opening_action_to_str(action));
+        }
+        value.set_item("actions", actions_value);
+
+        let mut alternatives_value = Value::array(0);
+        for coord in status.alternatives.iter() {
+            alternatives_value.push(self.coord_value(*coord));
+        }
+        value.set_item("alternatives", alternatives_value);
+
+        return value;
+    }
+
+    fn steps_value(&self, steps: &[CoordAndChess]) -> Value {
+        let mut steps_value = Value::array(0);
+        for step in steps.iter() {
+            let mut step_value = self.coord_value(step.coord);
+            step_value.set_item("chess", chess_to_str(step.chess));
+            steps_value.push(step_value);
+        }
+        return steps_value;
+    }
+
+    fn coord_value(&self, coord: Coord) -> Value {
+        let mut value = Value::new();
+        value.set_item("row", coord.row as i32);
+        value.set_item("col", coord.col as i32);
+        return value;
+    }
+
+    fn opening_result(&self, result: Result<(), Error>) -> Value {
+        let mut value = Value::new();
+
+        if let Err(error) = result {
+            value.set_item("error", error.message);
+        }
+
+        value.set_item("opening", self.get_opening_status());
+        return value;
+    }
+
+    // a win by continuous fours of the side to move, or else by threes and
+    // fours, the kind is "vcf", "vct", "none" or "unknown" when the solver
+    // ran out of nodes
+    fn on_show_forced_win(&self) -> Value {
+        let mut value = Value::new();
+        let mut kind = "none";
+        let mut sequence = Vec::new();
+
+        if !self.session.is_over() {
+            let solver = ThreatSolver::create_with_rule(&self.session.board(),
+                                                        self.session.rule());
+            for &(name, threat_kind) in [("vcf", ThreatKind::TkVcf),
+                                         ("vct", ThreatKind::TkVct)].iter() {
+                let solution = solver.solve(self.session.next_chess(), threat_kind);
+                match solution.status {
+                    SsWin => {
+                        kind = name;
+                        sequence = solution.sequence;
+                        break;
+                    },
+                    SsUnknown => kind = "unknown",
+                    SsNoWin => {},
+                }
+            }
+        }
+
+        // the two sides take turns from the side to move
+        let mut chess = self.session.next_chess();
+        let mut steps = Vec::new();
+        for coord in sequence.iter() {
+            steps.push(CoordAndChess{coord: *coord, chess});
+            chess = chess.get_different_chess();
+        }
+
+        // the solver only puts on the board, so every coord has a notation
+        let notation = write_moves(&sequence, self.session.board_size()).unwrap_or_default();
+        value.set_item("kind", kind);
+        value.set_item("notation", notation);
+        value.set_item("steps", self.steps_value(&steps));
+        return value;
+    }
+
+    // the bot plays on the board of the session while searching and takes
+    // its chess back before returning
     fn on_next_step(&self) -> Value {
         let mut value = Value::new();
-        let step = self.board_controller.get_simple_play();
+        if self.session.is_over() {
+            return value;
+        }
 
-        value.set_item("row", step.0);
-        value.set_item("col", step.1);
+        if let Some(coord) = self.bot.search(self.session.next_chess()).best_move {
+            value.set_item("row", coord.row as i32);
+            value.set_item("col", coord.col as i32);
+            value.set_item("hit_rate", self.bot.table_stats().hit_rate());
+        }
 
         return value;
     }
@@ -71,8 +459,104 @@ impl event::EventHandler for EventHandler {
 
     dispatch_script_call! {
         fn on_cross_point_click(i32, i32);
+        fn on_game_config_changed(String, String);
+        fn on_bot_move();
         fn on_back_move();
+        fn on_forward_move();
+        fn on_next_variation();
+        fn on_undo_to_my_last_move(String);
+        fn on_jump_to_move(i32);
         fn on_restart_game();
+        fn on_begin_setup();
+        fn on_setup_chess(i32, i32, String);
+        fn on_finish_setup(String);
         fn on_next_step();
+        fn on_show_forced_win();
+        fn on_save_game(String);
+        fn on_open_game(String);
+        fn on_opening_rule_changed(String);
+        fn on_choose_color(String);
+        fn on_place_two();
+        fn on_declare_alternatives(i32);
+        fn on_offer_alternatives();
+        fn on_select_alternative(i32, i32);
+        fn get_opening_status();
+    }
+}
+
+fn to_coord(row: i32, col: i32) -> Coord {
+    return Coord{row: row as usize, col: col as usize};
+}
+
+fn chess_to_str(chess: ChessType) -> &'static str {
+    match chess {
+        CtBlack => return "black",
+        CtWhite => return "white",
+    }
+}
+
+fn chess_from_str(chess: &str) -> Option<ChessType> {
+    match chess {
+        "black" => return Some(CtBlack),
+        "white" => return Some(CtWhite),
+        _ => return None,
+    }
+}
+
+fn difficulty_from_str(difficulty: &str) -> Option<Difficulty> {
+    match difficulty {
+        "easy" => return Some(DfEasy),
+        "normal" => return Some(DfNormal),
+        "hard" => return Some(DfHard),
+        _ => return None,
+    }
+}
+
+fn error_value(message: &str) -> Value {
+    let mut value = Value::new();
+    value.set_item("error", message);
+    return value;
+}
+
+fn forbidden_to_str(forbidden: ForbiddenType) -> &'static str {
+    match forbidden {
+        ForbiddenType::FtDoubleThree => return "double_three",
+        ForbiddenType::FtDoubleFour => return "double_four",
+        ForbiddenType::FtOverline => return "overline",
     }
-}
\ No newline at end of file
+}
+
+fn opening_rule_from_str(rule: &str) -> Option<OpeningRule> {
+    match rule {
+        "free" => return Some(OrFree),
+        "swap" => return Some(OrSwap),
+        "swap2" => return Some(OrSwap2),
+        "soosorv" => return Some(OrSoosorv),
+        "taraguchi" => return Some(OrTaraguchi),
+        _ => return None,
+    }
+}
+
+fn opening_phase_to_str(phase: OpeningPhase) -> &'static str {
+    match phase {
+        OpPlaceChess(_) => return "place_chess",
+        OpChooseColor => return "choose_color",
+        OpSwap2Choice => return "swap2_choice",
+        OpDeclareAlternatives => return "declare_alternatives",
+        OpPlaceAlternative => return "place_alternative",
+        OpSelectAlternative => return "select_alternative",
+        OpTaraguchiFifth => return "taraguchi_fifth",
+        OpFinished => return "finished",
+    }
+}
+
+fn opening_action_to_str(action: OpeningAction) -> &'static str {
+    match action {
+        OaPlace => return "place",
+        OaChooseColor => return "choose_color",
+        OaPlaceTwo => return "place_two",
+        OaDeclareAlternatives => return "declare_alternatives",
+        OaOfferAlternatives => return "offer_alternatives",
+        OaSelectAlternative => return "select_alternative",
+    }
+}
@@ -1,7 +1,7 @@
 #[macro_use]
 extern crate sciter;
 extern crate backend;
+extern crate bot;
 
 pub mod event_handler;
-pub mod board_controller;
 